@@ -0,0 +1,28 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use la_taupe::{
+    file_utils::bytes_to_img,
+    ocrs::{image_to_string_using_ocrs, image_to_string_with_engine, new_engine},
+};
+
+// cargo bench --bench ocrs, needs the models downloaded by download_models.sh
+fn ocrs(c: &mut Criterion) {
+    let content = std::fs::read("tests/fixtures/2ddoc/la_taupe.png").unwrap();
+    let img = bytes_to_img(content).unwrap();
+
+    let mut group = c.benchmark_group("ocrs");
+    group.sample_size(10);
+
+    // as before: the models loaded and the engine built on every call
+    group.bench_function("new engine per call", |b| {
+        b.iter(|| image_to_string_with_engine(&new_engine(), img.clone()))
+    });
+
+    group.bench_function("shared engine", |b| {
+        b.iter(|| image_to_string_using_ocrs(img.clone()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, ocrs);
+criterion_main!(benches);
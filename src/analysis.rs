@@ -1,14 +1,23 @@
-use std::path::Path;
+use std::{cell::OnceCell, path::Path, time::Duration};
 
+use image::DynamicImage;
 use serde::{Deserialize, Serialize};
 
-use crate::file_utils::{list_img_in_pdf, pdf_to_img_bytes};
+use crate::file_utils::{list_img_in_pdf, pdf_pages_to_img_bytes, pdf_to_img_bytes};
 use crate::rib::Rib;
 use crate::{
-    datamatrix::fetch_datamatrix,
-    file_utils::{bytes_to_img, pdf_bytes_to_string},
-    ocr::image_bytes_to_rib,
-    twoddoc::{ddoc::Ddoc, parse},
+    budget::{self, Status},
+    datamatrix::{fetch_datamatrix, fetch_qrcode},
+    epc::Epc,
+    file_utils::{pdf_bytes_to_layout, pdf_bytes_to_string},
+    mandate::Mandate,
+    ocr::{self, image_bytes_to_rib},
+    twoddoc::{
+        cross_check::{cross_check, CrossCheck},
+        ddoc::Ddoc,
+        parse,
+        registry::Registry,
+    },
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -18,15 +27,23 @@ pub enum Analysis {
     DdocAndRib {
         #[serde(rename = "2ddoc")]
         ddoc: Option<Ddoc>,
-        rib: Option<Rib>,
+        rib: Option<Box<Rib>>,
     },
     #[serde(rename = "rib")]
-    Rib { rib: Option<Rib> },
+    Rib { rib: Option<Box<Rib>> },
     #[serde(rename = "2ddoc")]
     Ddoc {
         #[serde(rename = "2ddoc")]
         ddoc: Option<Ddoc>,
     },
+    #[serde(rename = "2ddoc_cross_check")]
+    DdocCrossCheck {
+        #[serde(rename = "2ddoc")]
+        ddoc: Option<Ddoc>,
+        cross_check: Option<CrossCheck>,
+    },
+    #[serde(rename = "sepa_mandate")]
+    Mandate { mandate: Option<Mandate> },
 }
 
 #[derive(Deserialize, Copy, Clone, Debug)]
@@ -41,44 +58,207 @@ pub enum Type {
     Rib,
     #[serde(rename = "2ddoc")]
     Twoddoc,
+    #[serde(rename = "2ddoc_cross_check")]
+    TwoddocCrossCheck,
+    #[serde(rename = "sepa_mandate")]
+    Mandate,
 }
 
-fn vec_to_rib(content: Vec<u8>, name: &str) -> Result<Option<Rib>, String> {
-    let filetype = tree_magic_mini::from_u8(&content);
+impl Analysis {
+    // the analysis within the time budget, partial when it ran out
+    pub fn within(
+        timeout: Option<Duration>,
+        content: Vec<u8>,
+        hint: Option<Hint>,
+        name: &str,
+    ) -> (Result<Self, String>, Status) {
+        budget::with_budget(timeout, || Analysis::try_from((content, hint, name)))
+    }
+
+    // flags the 2D-Doc as already seen if its fingerprint is in the registry,
+    // otherwise records it
+    pub fn record_2ddoc(
+        &mut self,
+        registry: &Registry,
+        submitter: Option<&str>,
+    ) -> Result<(), String> {
+        let ddoc = match self {
+            Analysis::DdocAndRib { ddoc, .. }
+            | Analysis::Ddoc { ddoc }
+            | Analysis::DdocCrossCheck { ddoc, .. } => ddoc.as_mut(),
+            Analysis::Rib { .. } | Analysis::Mandate { .. } => None,
+        };
+
+        if let Some(ddoc) = ddoc {
+            ddoc.already_seen = registry.record(&ddoc.fingerprint(), submitter)?;
+        }
+
+        Ok(())
+    }
+
+    // compares the holder of the rib with the expected person or company name
+    pub fn match_holder(&mut self, expected: &str) {
+        let rib = match self {
+            Analysis::DdocAndRib { rib, .. } | Analysis::Rib { rib } => rib.as_deref_mut(),
+            Analysis::Ddoc { .. } | Analysis::DdocCrossCheck { .. } | Analysis::Mandate { .. } => {
+                None
+            }
+        };
+
+        if let Some(rib) = rib {
+            rib.match_holder(expected);
+        }
+    }
+}
+
+// the analyzed file, its first page is rendered at most once and shared by
+// the qr code, the 2d-doc and the ocr readings
+struct Document {
+    content: Vec<u8>,
+    filetype: &'static str,
+    page: OnceCell<Result<Vec<u8>, String>>,
+    image: OnceCell<Result<DynamicImage, String>>,
+}
+
+impl Document {
+    fn new(content: Vec<u8>) -> Self {
+        let filetype = tree_magic_mini::from_u8(&content);
+        Document {
+            content,
+            filetype,
+            page: OnceCell::new(),
+            image: OnceCell::new(),
+        }
+    }
+
+    // the png of the first page of a pdf, or the image itself
+    fn page(&self) -> Result<Vec<u8>, String> {
+        match self.filetype {
+            "application/pdf" => self
+                .page
+                .get_or_init(|| pdf_to_img_bytes(self.content.clone()))
+                .clone(),
+            "image/png" | "image/jpeg" => Ok(self.content.clone()),
+            _ => Err(format!("Unsupported file type: {}", self.filetype)),
+        }
+    }
+
+    fn image(&self) -> Result<DynamicImage, String> {
+        self.image
+            .get_or_init(|| {
+                // empty when pdftoppm ran out of time
+                image::load_from_memory(&self.page()?)
+                    .map_err(|e| format!("Failed to load image from bytes: {}", e))
+            })
+            .clone()
+    }
+}
+
+// the EPC qr code, when present, is preferred to the printed iban
+fn vec_to_rib(document: &Document, name: &str) -> Result<Option<Rib>, String> {
+    let epc = vec_to_epc(document);
+    let rib = match vec_to_printed_rib(document, name) {
+        Ok(rib) => rib,
+        // the qr code of an image is read without ocr
+        Err(e) if epc.is_some() => {
+            log::warn!("{}", e);
+            None
+        }
+        Err(e) => return Err(e),
+    };
+
+    Ok(match (rib, epc) {
+        (Some(rib), Some(epc)) => Some(rib.with_epc(epc)),
+        (None, Some(epc)) => Rib::from_epc(epc),
+        (rib, None) => rib,
+    })
+}
+
+fn vec_to_epc(document: &Document) -> Option<Epc> {
+    let payload = fetch_qrcode(document.image().ok()?)?;
+
+    Epc::parse(&payload)
+        .inspect_err(|e| log::trace!("qr code ignored: {}", e))
+        .ok()
+}
+
+fn vec_to_printed_rib(document: &Document, name: &str) -> Result<Option<Rib>, String> {
+    let content = document.content.clone();
+    let filetype = document.filetype;
 
     if filetype == "application/pdf" {
-        let string_rib = pdf_bytes_to_string(content.clone());
+        let string_rib = pdf_bytes_to_string(content.clone())?;
 
         if !string_rib.trim().is_empty() {
-            let rib = Rib::parse(string_rib);
+            let rib = Rib::parse_document_with_layout(string_rib, || {
+                pdf_bytes_to_layout(content.clone())
+                    .inspect_err(|e| log::warn!("{}", e))
+                    .ok()
+            });
             if rib.is_some() {
                 Ok(rib)
             // if there is only one image in PDF, it could be a scan of a RIB
             // with some poorly parse text.
             // don't try it for all as it is costly
-            } else if list_img_in_pdf(content.clone()) == 1 {
-                let img = pdf_to_img_bytes(content);
-                Ok(image_bytes_to_rib(img, name))
+            } else if !budget::exhausted()
+                && ocr::is_available()
+                && list_img_in_pdf(content.clone()).is_ok_and(|count| count == 1)
+            {
+                Ok(image_bytes_to_rib(document.page()?, name))
             } else {
                 Ok(None)
             }
         } else {
-            let img = pdf_to_img_bytes(content);
-            Ok(image_bytes_to_rib(img, name))
+            scanned_pdf_to_rib(document, name)
         }
     } else if filetype == "image/png" || filetype == "image/jpeg" {
+        require_ocr()?;
         Ok(image_bytes_to_rib(content, name))
     } else if filetype == "text/plain" {
         let string_rib = String::from_utf8(content)
             .map_err(|_| "Failed to convert bytes to string".to_string())?;
-        Ok(Rib::parse(string_rib))
+        Ok(Rib::parse_document(string_rib))
     } else {
         Err(format!("Unsupported file type: {}", filetype))
     }
 }
 
-fn vec_to_ddoc(content: Vec<u8>) -> Result<Option<Ddoc>, String> {
-    let img = bytes_to_img(content)?;
+// a scanned rib or bank statement is read on its first page, the other pages
+// are only rendered for a statement, its closing balance is on the last one
+fn scanned_pdf_to_rib(document: &Document, name: &str) -> Result<Option<Rib>, String> {
+    require_ocr()?;
+
+    let Some(rib) = image_bytes_to_rib(document.page()?, name) else {
+        return Ok(None);
+    };
+    if !rib.is_statement() {
+        return Ok(Some(rib));
+    }
+
+    let mut texts = vec![];
+    if !budget::exhausted() {
+        for page in pdf_pages_to_img_bytes(document.content.clone(), 2)? {
+            // the statement is read on the pages done in time
+            if budget::exhausted() {
+                break;
+            }
+            texts.extend(ocr::image_bytes_to_text(page));
+        }
+    }
+
+    Ok(Some(rib.with_following_pages(&texts.join("\x0c"))))
+}
+
+fn require_ocr() -> Result<(), String> {
+    if ocr::is_available() {
+        Ok(())
+    } else {
+        Err("No ocr engine is available to read the image".to_string())
+    }
+}
+
+fn vec_to_ddoc(document: &Document) -> Result<Option<Ddoc>, String> {
+    let img = document.image()?;
 
     if let Some(datamatrix) = fetch_datamatrix(img) {
         Ok(parse(&datamatrix))
@@ -87,26 +267,83 @@ fn vec_to_ddoc(content: Vec<u8>) -> Result<Option<Ddoc>, String> {
     }
 }
 
+// the visible text of the document, from the text layer of the pdf or by ocr
+fn vec_to_text(document: &Document) -> Result<String, String> {
+    if document.filetype == "application/pdf" {
+        let text = pdf_bytes_to_string(document.content.clone())?;
+
+        if !text.trim().is_empty() {
+            return Ok(text);
+        }
+    } else if document.filetype == "text/plain" {
+        return String::from_utf8(document.content.clone())
+            .map_err(|_| "Failed to convert bytes to string".to_string());
+    }
+
+    require_ocr()?;
+    ocr::image_bytes_to_text(document.page()?)
+        .ok_or_else(|| "Failed to load image from bytes".to_string())
+}
+
+fn vec_to_ddoc_cross_check(
+    document: &Document,
+) -> Result<(Option<Ddoc>, Option<CrossCheck>), String> {
+    let ddoc = vec_to_ddoc(document)?;
+
+    if let Some(ddoc) = ddoc {
+        let text = vec_to_text(document)?;
+        let cross_check = cross_check(&ddoc, &text);
+
+        Ok((Some(ddoc), Some(cross_check)))
+    } else {
+        Ok((None, None))
+    }
+}
+
 impl TryFrom<(Vec<u8>, Option<Hint>, &str)> for Analysis {
     type Error = String;
 
     fn try_from((content, hint, name): (Vec<u8>, Option<Hint>, &str)) -> Result<Self, String> {
+        let document = Document::new(content);
+
         match hint {
             Some(Hint::Type(Type::Rib)) => {
-                let rib = vec_to_rib(content, name)?;
+                let rib = vec_to_rib(&document, name)?;
 
-                Ok(Analysis::Rib { rib })
+                Ok(Analysis::Rib {
+                    rib: rib.map(Box::new),
+                })
             }
             Some(Hint::Type(Type::Twoddoc)) => {
-                let ddoc = vec_to_ddoc(content)?;
+                let ddoc = vec_to_ddoc(&document)?;
 
                 Ok(Analysis::Ddoc { ddoc })
             }
+            Some(Hint::Type(Type::TwoddocCrossCheck)) => {
+                let (ddoc, cross_check) = vec_to_ddoc_cross_check(&document)?;
+
+                Ok(Analysis::DdocCrossCheck { ddoc, cross_check })
+            }
+            Some(Hint::Type(Type::Mandate)) => {
+                let text = vec_to_text(&document)?;
+
+                Ok(Analysis::Mandate {
+                    mandate: Mandate::parse(&text),
+                })
+            }
             None => {
-                let rib = vec_to_rib(content.clone(), name).unwrap_or(None);
-                let ddoc = vec_to_ddoc(content).unwrap_or(None);
+                let rib = vec_to_rib(&document, name);
+                let ddoc = vec_to_ddoc(&document);
 
-                Ok(Analysis::DdocAndRib { ddoc, rib })
+                // an error when nothing could be read, ex: a pdf without poppler, or when
+                // the rib failed and there is no 2d-doc, rather than an empty response
+                match (rib, ddoc) {
+                    (Err(e), Err(_)) | (Err(e), Ok(None)) => Err(e),
+                    (rib, ddoc) => Ok(Analysis::DdocAndRib {
+                        ddoc: ddoc.unwrap_or(None),
+                        rib: rib.unwrap_or(None).map(Box::new),
+                    }),
+                }
             }
         }
     }
@@ -0,0 +1,173 @@
+use std::{
+    cell::Cell,
+    io::{Read, Write},
+    process::{Child, Command, Stdio},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// complete, or timeout when a step was cut short or skipped
+// and the result may be partial
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    #[default]
+    Complete,
+    Timeout,
+}
+
+// an analysis runs on a single thread, from the download to the response
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    static TIMED_OUT: Cell<bool> = const { Cell::new(false) };
+}
+
+// LA_TAUPE_ANALYSIS_TIMEOUT in seconds, 0 for no limit
+pub fn timeout_from_env() -> Option<Duration> {
+    let secs = std::env::var("LA_TAUPE_ANALYSIS_TIMEOUT")
+        .ok()
+        .and_then(|secs| {
+            secs.trim()
+                .parse::<u64>()
+                .inspect_err(|_| log::warn!("invalid LA_TAUPE_ANALYSIS_TIMEOUT: {}", secs))
+                .ok()
+        })
+        .unwrap_or(DEFAULT_TIMEOUT_SECS);
+
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+// runs f with a deadline shared by every step of the analysis
+pub fn with_budget<T>(timeout: Option<Duration>, f: impl FnOnce() -> T) -> (T, Status) {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let previous = (DEADLINE.replace(deadline), TIMED_OUT.replace(false));
+
+    let result = f();
+
+    let status = if TIMED_OUT.get() {
+        Status::Timeout
+    } else {
+        Status::Complete
+    };
+    DEADLINE.set(previous.0);
+    TIMED_OUT.set(previous.1);
+
+    (result, status)
+}
+
+// true once the deadline is passed, the analysis is then flagged as timed out
+pub fn exhausted() -> bool {
+    let exhausted = DEADLINE
+        .get()
+        .is_some_and(|deadline| Instant::now() >= deadline);
+
+    if exhausted {
+        TIMED_OUT.set(true);
+    }
+
+    exhausted
+}
+
+// spawns the command, writes input to its stdin and returns its stdout.
+// the process is killed when the budget runs out, its output so far is returned
+pub fn run(command: &mut Command, input: Vec<u8>) -> std::io::Result<Vec<u8>> {
+    let mut child = command.stdin(Stdio::piped()).spawn()?;
+
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| std::io::Error::other("Failed to open stdin"))?;
+    std::thread::spawn(move || {
+        // the process may exit or be killed before reading everything
+        let _ = stdin.write_all(&input);
+    });
+
+    let stdout = child.stdout.take().map(read_in_background);
+    // stderr is drained so that a verbose process does not block on a full pipe
+    let stderr = child.stderr.take().map(read_in_background);
+
+    wait_or_kill(&mut child, command)?;
+
+    if let Some(handle) = stderr {
+        let _ = handle.join();
+    }
+
+    Ok(stdout
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default())
+}
+
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buffer = vec![];
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+fn wait_or_kill(child: &mut Child, command: &Command) -> std::io::Result<()> {
+    loop {
+        if child.try_wait()?.is_some() {
+            return Ok(());
+        }
+
+        if exhausted() {
+            log::warn!(
+                "{} killed, the analysis ran out of time",
+                command.get_program().to_string_lossy()
+            );
+            child.kill()?;
+            child.wait()?;
+            return Ok(());
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let (output, status) = with_budget(Some(Duration::from_secs(10)), || {
+            run(
+                Command::new("cat").stdout(Stdio::piped()),
+                b"la taupe".to_vec(),
+            )
+        });
+
+        assert_eq!(output.unwrap(), b"la taupe");
+        assert_eq!(status, Status::Complete);
+    }
+
+    #[test]
+    fn test_run_out_of_time() {
+        let start = Instant::now();
+        let (output, status) = with_budget(Some(Duration::from_millis(100)), || {
+            run(
+                Command::new("sleep").arg("10").stdout(Stdio::piped()),
+                vec![],
+            )
+        });
+
+        assert!(output.unwrap().is_empty());
+        assert_eq!(status, Status::Timeout);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(!exhausted());
+    }
+
+    #[test]
+    fn test_without_budget() {
+        let (exhausted, status) = with_budget(None, exhausted);
+
+        assert!(!exhausted);
+        assert_eq!(status, Status::Complete);
+    }
+}
@@ -9,10 +9,19 @@ use rxing::{
 };
 
 pub fn fetch_datamatrix(img: DynamicImage) -> Option<String> {
+    fetch_barcode(img, BarcodeFormat::DATA_MATRIX)
+}
+
+// the qr codes of the documents, ex: the EPC payment code of ribs and invoices
+pub fn fetch_qrcode(img: DynamicImage) -> Option<String> {
+    fetch_barcode(img, BarcodeFormat::QR_CODE)
+}
+
+fn fetch_barcode(img: DynamicImage, format: BarcodeFormat) -> Option<String> {
     let mut multi_format_reader = MultiFormatReader::default();
 
     let hints = DecodeHints::default()
-        .with(PossibleFormats(HashSet::from([BarcodeFormat::DATA_MATRIX])))
+        .with(PossibleFormats(HashSet::from([format])))
         .with(TryHarder(true));
 
     multi_format_reader.set_hints(&hints);
@@ -38,4 +47,18 @@ mod tests {
 
         assert_eq!(fetch_datamatrix(img), Some(result.to_string()));
     }
+
+    #[test]
+    fn test_fetch_qrcode() {
+        use rxing::{MultiFormatWriter, Writer};
+
+        let payload = "BCD\n002\n1\nSCT\n\nHenri Matisse\nFR7630001000644919009562088";
+        let matrix = MultiFormatWriter
+            .encode(payload, &BarcodeFormat::QR_CODE, 300, 300)
+            .unwrap();
+        let img = DynamicImage::from(&matrix);
+
+        assert_eq!(fetch_qrcode(img.clone()), Some(payload.to_string()));
+        assert_eq!(fetch_datamatrix(img), None);
+    }
 }
@@ -0,0 +1,108 @@
+use iban::Iban;
+use serde::{Deserialize, Serialize};
+
+// the payload of the EPC069-12 "BCD" qr code, one field per line:
+// service tag, version, character set, identification, bic, name, iban,
+// amount, purpose, structured reference, remittance text, information
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Epc {
+    pub version: String,
+    pub bic: Option<String>,
+    pub name: String,
+    pub iban: String,
+    // ex: EUR12.50
+    pub amount: Option<String>,
+    pub purpose: Option<String>,
+    pub reference: Option<String>,
+    pub remittance: Option<String>,
+}
+
+impl Epc {
+    pub fn parse(payload: &str) -> Result<Self, String> {
+        let lines = payload
+            .lines()
+            .map(|line| line.trim())
+            .collect::<Vec<&str>>();
+        let field = |index: usize| {
+            lines
+                .get(index)
+                .filter(|field| !field.is_empty())
+                .map(|field| field.to_string())
+        };
+
+        if field(0).as_deref() != Some("BCD") {
+            return Err("not an EPC qr code".to_string());
+        }
+
+        let version = field(1).ok_or("missing EPC version")?;
+        if version != "001" && version != "002" {
+            return Err(format!("unknown EPC version: {}", version));
+        }
+
+        if field(3).as_deref() != Some("SCT") {
+            return Err("the EPC qr code is not a SEPA credit transfer".to_string());
+        }
+
+        // the bic is optional since the version 002
+        let bic = field(4).map(|bic| bic.replace(" ", "").to_uppercase());
+        if version == "001" && bic.is_none() {
+            return Err("missing bic in EPC version 001".to_string());
+        }
+
+        let name = field(5).ok_or("missing beneficiary name")?;
+
+        let iban = field(6)
+            .ok_or("missing iban")?
+            .parse::<Iban>()
+            .map_err(|_| "invalid iban in the EPC qr code".to_string())?;
+
+        Ok(Epc {
+            version,
+            bic,
+            name,
+            iban: iban.to_string(),
+            amount: field(7),
+            purpose: field(8),
+            reference: field(9),
+            remittance: field(10),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let payload = "BCD\n002\n1\nSCT\nBPOTBEB1\nRed Cross of Belgium\nBE72000000001616\nEUR1\nCHAR\n\nUrgency fund\n";
+
+        assert_eq!(
+            Epc::parse(payload),
+            Ok(Epc {
+                version: "002".to_string(),
+                bic: Some("BPOTBEB1".to_string()),
+                name: "Red Cross of Belgium".to_string(),
+                iban: "BE72 0000 0000 1616".to_string(),
+                amount: Some("EUR1".to_string()),
+                purpose: Some("CHAR".to_string()),
+                reference: None,
+                remittance: Some("Urgency fund".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Epc::parse("https://example.com").is_err());
+        assert!(
+            Epc::parse("BCD\n001\n1\nSCT\n\nHenri Matisse\nFR7630001000644919009562088").is_err()
+        );
+        assert!(
+            Epc::parse("BCD\n002\n1\nSCT\n\nHenri Matisse\nFR7630001000644919009562089").is_err()
+        );
+        assert!(
+            Epc::parse("BCD\n002\n1\nSCT\n\nHenri Matisse\nFR7630001000644919009562088").is_ok()
+        );
+    }
+}
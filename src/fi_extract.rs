@@ -1,31 +1,173 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
 
 // fetch from https://www.ecb.europa.eu/stats/financial_corporations/list_of_financial_institutions/html/monthly_list-MID.en.html
-// then iconv -f UTF-16 -t UTF-8 fi_mrr_csv_250630.csv | grep "^FR" | cut -f1,4 > src/riad_bank_name.csv
+// then la_taupe banks import fi_mrr_csv_250630.csv --country FR > src/riad_bank_name.csv
+// the bundled file predates the type and head office columns, they are empty until regenerated
 const RIAD_CSV: &str = include_str!("./riad_bank_name.csv");
 
+// shared by all the analyses, built on first use from the file set in
+// LA_TAUPE_MFI_CSV (the raw ecb csv) or from the bundled csv
+static BANK_REFERENCE: LazyLock<RwLock<IbanToBankName>> = LazyLock::new(|| {
+    let reference = std::env::var("LA_TAUPE_MFI_CSV")
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+        .and_then(|path| match IbanToBankName::from_mfi_file(&path) {
+            Ok(reference) => Some(reference),
+            Err(e) => {
+                log::warn!("{}, using the bundled bank names", e);
+                None
+            }
+        })
+        .unwrap_or_default();
+
+    RwLock::new(reference)
+});
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HeadOffice {
+    pub name: String,
+    pub riad_code: Option<String>,
+    pub country: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Institution {
+    pub riad_code: String,
+    pub name: String,
+    pub lei: Option<String>,
+    pub country: String,
+    // the category of the ecb list, ex: Credit Institution
+    pub institution_type: Option<String>,
+    pub address: Option<String>,
+    pub postal_code: Option<String>,
+    pub city: Option<String>,
+    pub head_office: Option<HeadOffice>,
+}
+
 pub struct IbanToBankName {
-    data: HashMap<String, String>,
+    data: HashMap<String, Institution>,
 }
 
 impl IbanToBankName {
     pub fn new() -> Self {
         let mut data = HashMap::new();
 
+        // riad code, name, then optionally type, head office name, riad code and country
         for line in RIAD_CSV.lines() {
-            // Skip header
             let fields: Vec<&str> = line.split('\t').collect();
             if fields.len() >= 2 {
                 let riad_code = fields[0].to_string();
-                let name = fields[1].to_string();
-                data.insert(riad_code, name);
+                let optional = |index: usize| {
+                    fields
+                        .get(index)
+                        .map(|f| f.trim().to_string())
+                        .filter(|f| !f.is_empty())
+                };
+
+                let head_office = optional(3).map(|name| HeadOffice {
+                    name,
+                    riad_code: optional(4),
+                    country: optional(5),
+                });
+
+                let institution = Institution {
+                    country: riad_code.chars().take(2).collect(),
+                    riad_code: riad_code.clone(),
+                    name: fields[1].to_string(),
+                    lei: None,
+                    institution_type: optional(2),
+                    address: None,
+                    postal_code: None,
+                    city: None,
+                    head_office,
+                };
+                data.insert(riad_code, institution);
             }
         }
 
         Self { data }
     }
 
+    // the monthly list of the ecb, encoded in utf-16 with all its columns
+    pub fn from_mfi_csv(bytes: &[u8]) -> Result<Self, String> {
+        let content = decode(bytes)?;
+        let mut lines = content.lines();
+
+        let header = lines
+            .next()
+            .ok_or("empty mfi csv")?
+            .split('\t')
+            .map(|column| column.trim().trim_matches('"'))
+            .collect::<Vec<&str>>();
+        let index = |name: &str| header.iter().position(|column| *column == name);
+
+        let riad_code_index = index("RIAD_CODE").ok_or("no RIAD_CODE column in the mfi csv")?;
+        let name_index = index("NAME").ok_or("no NAME column in the mfi csv")?;
+
+        let mut data = HashMap::new();
+
+        for line in lines {
+            let fields = line
+                .split('\t')
+                .map(|field| field.trim().trim_matches('"'))
+                .collect::<Vec<&str>>();
+            let field = |index: Option<usize>| {
+                index
+                    .and_then(|index| fields.get(index))
+                    .filter(|field| !field.is_empty())
+                    .map(|field| field.to_string())
+            };
+
+            let (Some(riad_code), Some(name)) =
+                (field(Some(riad_code_index)), field(Some(name_index)))
+            else {
+                continue;
+            };
+
+            let head_office = field(index("HEAD_NAME")).map(|name| HeadOffice {
+                name,
+                riad_code: field(index("HEAD_RIAD_CODE")),
+                country: field(index("HEAD_COUNTRY_OF_REGISTRATION")),
+            });
+
+            let institution = Institution {
+                country: field(index("COUNTRY_OF_REGISTRATION"))
+                    .unwrap_or_else(|| riad_code.chars().take(2).collect()),
+                riad_code: riad_code.clone(),
+                name,
+                lei: field(index("LEI")),
+                institution_type: field(index("CATEGORY")),
+                address: field(index("ADDRESS")),
+                postal_code: field(index("POSTAL")),
+                city: field(index("CITY")),
+                head_office,
+            };
+            data.insert(riad_code, institution);
+        }
+
+        if data.is_empty() {
+            return Err("no institution in the mfi csv".to_string());
+        }
+
+        Ok(Self { data })
+    }
+
+    pub fn from_mfi_file(path: &str) -> Result<Self, String> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| format!("unable to read the mfi csv {}: {}", path, e))?;
+        Self::from_mfi_csv(&bytes)
+    }
+
     pub fn bank_name(&self, iban: &str) -> Option<String> {
+        self.institution(iban).map(|i| i.name.clone())
+    }
+
+    pub fn institution(&self, iban: &str) -> Option<&Institution> {
         let iban_without_space = iban.replace(" ", "");
         let country_code = iban_without_space.chars().take(2).collect::<String>();
         let bank_code = iban_without_space
@@ -35,7 +177,48 @@ impl IbanToBankName {
             .collect::<String>();
         let riad_code = format!("{}{}", country_code, bank_code);
 
-        self.data.get(&riad_code).cloned()
+        self.data.get(&riad_code)
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // the format of the bundled csv, sorted by riad code
+    pub fn to_bundled_csv(&self, country: Option<&str>) -> String {
+        let mut institutions = self
+            .data
+            .values()
+            .filter(|i| country.is_none_or(|country| i.country == country))
+            .collect::<Vec<&Institution>>();
+        institutions.sort_by(|a, b| a.riad_code.cmp(&b.riad_code));
+
+        institutions
+            .iter()
+            .map(|i| {
+                let head_office = i.head_office.as_ref();
+                [
+                    i.riad_code.as_str(),
+                    i.name.as_str(),
+                    i.institution_type.as_deref().unwrap_or_default(),
+                    head_office.map(|h| h.name.as_str()).unwrap_or_default(),
+                    head_office
+                        .and_then(|h| h.riad_code.as_deref())
+                        .unwrap_or_default(),
+                    head_office
+                        .and_then(|h| h.country.as_deref())
+                        .unwrap_or_default(),
+                ]
+                .join("\t")
+                .trim_end()
+                .to_string()
+                    + "\n"
+            })
+            .collect()
     }
 }
 
@@ -45,10 +228,58 @@ impl Default for IbanToBankName {
     }
 }
 
+pub fn bank_name(iban: &str) -> Option<String> {
+    BANK_REFERENCE.read().unwrap().bank_name(iban)
+}
+
+pub fn institution(iban: &str) -> Option<Institution> {
+    BANK_REFERENCE.read().unwrap().institution(iban).cloned()
+}
+
+// replaces the shared reference by the content of a raw ecb csv,
+// returns the number of institutions
+pub fn import_mfi_file(path: &str) -> Result<usize, String> {
+    let reference = IbanToBankName::from_mfi_file(path)?;
+    let len = reference.len();
+    *BANK_REFERENCE.write().unwrap() = reference;
+    Ok(len)
+}
+
+// utf-16 with its bom as published by the ecb, or utf-8 once converted
+fn decode(bytes: &[u8]) -> Result<String, String> {
+    let utf16 = |bytes: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        let units = bytes
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]))
+            .collect::<Vec<u16>>();
+        String::from_utf16(&units).map_err(|e| format!("invalid utf-16 mfi csv: {}", e))
+    };
+
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => {
+            String::from_utf8(rest.to_vec()).map_err(|e| format!("invalid mfi csv: {}", e))
+        }
+        _ => String::from_utf8(bytes.to_vec()).map_err(|e| format!("invalid mfi csv: {}", e)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const MFI_CSV: &str = "RIAD_CODE\tLEI\tCOUNTRY_OF_REGISTRATION\tNAME\tBOX\tADDRESS\tPOSTAL\tCITY\tCATEGORY\tHEAD_COUNTRY_OF_REGISTRATION\tHEAD_NAME\tHEAD_RIAD_CODE\tHEAD_LEI
+FR14706\tLEI00000000000000001\tFR\tCaisse régionale de crédit agricole mutuel Atlantique Vendée\t\tRoute de Paris\t44949\tNantes\tCredit Institution\tFR\tCrédit Agricole S.A.\tFR30006\tLEI00000000000000001
+DE37040\t\tDE\tCommerzbank AG\t\tKaiserplatz\t60311\tFrankfurt am Main\tCredit Institution\t\t\t\t
+";
+
+    fn utf16_le(s: &str) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(s.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        bytes
+    }
+
     #[test]
     fn test_get_bic_and_name() {
         let fi_extract = IbanToBankName::new();
@@ -60,4 +291,53 @@ mod tests {
         let result = fi_extract.bank_name("NONEXISTENT");
         assert_eq!(result, None);
     }
+
+    #[test]
+    fn test_from_mfi_csv() {
+        let reference = IbanToBankName::from_mfi_csv(&utf16_le(MFI_CSV)).unwrap();
+        assert_eq!(reference.len(), 2);
+
+        let institution = reference
+            .institution("FR76 1470 6000 3060 0018 2530 127")
+            .unwrap();
+        assert_eq!(
+            institution.institution_type,
+            Some("Credit Institution".to_string())
+        );
+        assert_eq!(institution.country, "FR");
+        assert_eq!(institution.city, Some("Nantes".to_string()));
+        assert_eq!(
+            institution.head_office,
+            Some(HeadOffice {
+                name: "Crédit Agricole S.A.".to_string(),
+                riad_code: Some("FR30006".to_string()),
+                country: Some("FR".to_string()),
+            })
+        );
+
+        assert_eq!(
+            reference.to_bundled_csv(Some("FR")),
+            "FR14706\tCaisse régionale de crédit agricole mutuel Atlantique Vendée\tCredit Institution\tCrédit Agricole S.A.\tFR30006\tFR\n"
+        );
+
+        assert!(IbanToBankName::from_mfi_csv(b"NAME\tCITY\n").is_err());
+    }
+
+    #[test]
+    fn test_to_bundled_csv() {
+        let reference = IbanToBankName::from_mfi_csv(MFI_CSV.as_bytes()).unwrap();
+        assert_eq!(
+            reference.to_bundled_csv(Some("DE")),
+            "DE37040\tCommerzbank AG\tCredit Institution\n"
+        );
+    }
+
+    #[test]
+    fn test_shared_reference() {
+        assert_eq!(
+            bank_name("FR0042529ANDSTUFF"),
+            Some("Edmond de Rothschild (France)".to_string())
+        );
+        assert!(import_mfi_file("does/not/exist.csv").is_err());
+    }
 }
@@ -1,74 +1,112 @@
 use image::DynamicImage;
-use std::{
-    io::Write,
-    process::{Command, Stdio},
+
+use crate::{
+    budget,
+    layout::Layout,
+    tools::{require, tools},
 };
+use std::process::{Command, Stdio};
 
 pub fn bytes_to_img(bytes: Vec<u8>) -> Result<DynamicImage, String> {
     let filetype = tree_magic_mini::from_u8(&bytes);
 
     match filetype {
         "application/pdf" => {
-            let buffer = pdf_to_img_bytes(bytes.clone());
-            let img = image::load_from_memory(&buffer).expect("Failed to load image from bytes");
-            Ok(img)
-        }
-        "image/png" | "image/jpeg" => {
-            Ok(image::load_from_memory(&bytes).expect("Failed to load image from bytes"))
+            // empty when pdftoppm ran out of time
+            let buffer = pdf_to_img_bytes(bytes.clone())?;
+            image::load_from_memory(&buffer)
+                .map_err(|e| format!("Failed to load image from bytes: {}", e))
         }
+        "image/png" | "image/jpeg" => image::load_from_memory(&bytes)
+            .map_err(|e| format!("Failed to load image from bytes: {}", e)),
         _ => Err(format!("Unsupported file type: {}", filetype)),
     }
 }
 
-pub fn pdf_bytes_to_string(bytes: Vec<u8>) -> String {
-    let mut child = Command::new("pdftotext")
-        .args(["-layout", "-", "-"])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .spawn()
-        .expect("Failed to start pdftotext");
+pub fn pdf_bytes_to_string(bytes: Vec<u8>) -> Result<String, String> {
+    require("pdftotext", &tools().pdftotext)?;
+
+    let output = budget::run(
+        Command::new("pdftotext")
+            .args(["-layout", "-", "-"])
+            .stdout(Stdio::piped()),
+        bytes,
+    )
+    .map_err(|e| format!("Failed to run pdftotext: {}", e))?;
 
-    let mut stdin = child.stdin.take().expect("Failed to open stdin");
-    std::thread::spawn(move || {
-        stdin.write_all(&bytes).expect("Failed to write to stdin");
-    });
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+// the words of the text layer with their boxes, see layout.rs
+pub fn pdf_bytes_to_layout(bytes: Vec<u8>) -> Result<Layout, String> {
+    require("pdftotext", &tools().pdftotext)?;
 
-    let output = child.wait_with_output().expect("Failed to wait on child");
+    let output = budget::run(
+        Command::new("pdftotext")
+            .args(["-bbox-layout", "-", "-"])
+            .stdout(Stdio::piped()),
+        bytes,
+    )
+    .map_err(|e| format!("Failed to run pdftotext: {}", e))?;
 
-    String::from_utf8_lossy(&output.stdout).to_string()
+    Layout::from_bbox_layout(&String::from_utf8_lossy(&output))
 }
 
-pub fn pdf_to_img_bytes(file: Vec<u8>) -> Vec<u8> {
-    let mut child = Command::new("pdftoppm")
-        .args(["-png", "-singlefile"])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .spawn()
-        .expect("failed to execute process");
-
-    let mut stdin = child.stdin.take().expect("Failed to open stdin");
-    std::thread::spawn(move || {
-        stdin.write_all(&file).expect("Failed to write to stdin");
-    });
-
-    let output = child.wait_with_output().expect("Failed to wait on child");
-    output.stdout
+pub fn pdf_to_img_bytes(file: Vec<u8>) -> Result<Vec<u8>, String> {
+    require("pdftoppm", &tools().pdftoppm)?;
+
+    budget::run(
+        Command::new("pdftoppm")
+            .args(["-png", "-singlefile"])
+            .stdout(Stdio::piped()),
+        file,
+    )
+    .map_err(|e| format!("Failed to run pdftoppm: {}", e))
 }
 
-pub fn list_img_in_pdf(file: Vec<u8>) -> usize {
-    let mut child = Command::new("pdfimages")
-        .args(["-list", "-"])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .spawn()
-        .expect("failed to execute process");
+pub fn list_img_in_pdf(file: Vec<u8>) -> Result<usize, String> {
+    require("pdfimages", &tools().pdfimages)?;
+
+    let output = budget::run(
+        Command::new("pdfimages")
+            .args(["-list", "-"])
+            .stdout(Stdio::piped()),
+        file,
+    )
+    .map_err(|e| format!("Failed to run pdfimages: {}", e))?;
+
+    // Subtract header lines, a killed process may not have printed them
+    Ok(String::from_utf8_lossy(&output)
+        .lines()
+        .count()
+        .saturating_sub(2))
+}
+
+// one png per page from the first one given, in order
+pub fn pdf_pages_to_img_bytes(file: Vec<u8>, first: usize) -> Result<Vec<Vec<u8>>, String> {
+    require("pdftoppm", &tools().pdftoppm)?;
+
+    let dir = tempfile::tempdir().map_err(|e| format!("Failed to create temp dir: {}", e))?;
+    let prefix = dir.path().join("page");
 
-    let mut stdin = child.stdin.take().expect("Failed to open stdin");
-    std::thread::spawn(move || {
-        stdin.write_all(&file).expect("Failed to write to stdin");
-    });
+    budget::run(
+        Command::new("pdftoppm")
+            .args(["-png", "-f", &first.to_string(), "-"])
+            .arg(&prefix),
+        file,
+    )
+    .map_err(|e| format!("Failed to run pdftoppm: {}", e))?;
 
-    let output = child.wait_with_output().expect("Failed to wait on child");
+    // page-1.png, or page-01.png with 10 pages or more,
+    // only the pages written so far when pdftoppm was killed
+    let mut paths = std::fs::read_dir(dir.path())
+        .map_err(|e| format!("Failed to read pdftoppm output: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    paths.sort();
 
-    String::from_utf8_lossy(&output.stdout).lines().count() - 2 // Subtract header lines
+    paths
+        .iter()
+        .map(|path| std::fs::read(path).map_err(|e| format!("Failed to read page: {}", e)))
+        .collect()
 }
@@ -2,7 +2,11 @@ use actix_web::{http::header::ContentType, post, web, HttpResponse, Responder};
 use reqwest::Response;
 use serde::{Deserialize, Serialize};
 
-use crate::analysis::{Analysis, Hint};
+use crate::{
+    analysis::{Analysis, Hint},
+    budget::{self, Status},
+    twoddoc::registry::Registry,
+};
 
 const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
 
@@ -10,6 +14,10 @@ const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
 struct RequestedFile {
     url: String,
     hint: Option<Hint>,
+    // opaque reference of the submitter, stored in the 2D-Doc registry
+    submitter: Option<String>,
+    // name of the person or company the RIB should belong to
+    expected_holder: Option<String>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -19,6 +27,14 @@ pub struct AnalysisError {
     pub body: Option<String>,
 }
 
+// the analysis and whether it ran out of time
+#[derive(Serialize)]
+struct AnalysisResponse {
+    #[serde(flatten)]
+    analysis: Analysis,
+    status: Status,
+}
+
 #[post("/analyze")]
 pub async fn analyze(requested_file: web::Json<RequestedFile>) -> impl Responder {
     let response = match reqwest::get(&requested_file.url).await {
@@ -34,7 +50,13 @@ pub async fn analyze(requested_file: web::Json<RequestedFile>) -> impl Responder
     };
 
     if response.status().is_success() {
-        handle_response(response, requested_file.hint).await
+        handle_response(
+            response,
+            requested_file.hint,
+            requested_file.submitter.as_deref(),
+            requested_file.expected_holder.as_deref(),
+        )
+        .await
     } else {
         handle_error(response).await
     }
@@ -70,7 +92,12 @@ async fn handle_error(resp: Response) -> HttpResponse {
     }
 }
 
-async fn handle_response(mut resp: Response, hint: Option<Hint>) -> HttpResponse {
+async fn handle_response(
+    mut resp: Response,
+    hint: Option<Hint>,
+    submitter: Option<&str>,
+    expected_holder: Option<&str>,
+) -> HttpResponse {
     let len = resp
         .headers()
         .get("content-length")
@@ -113,16 +140,37 @@ async fn handle_response(mut resp: Response, hint: Option<Hint>) -> HttpResponse
             });
     }
 
-    match Analysis::try_from((bytes, hint, "remote_file")) {
-        Ok(analysis) => HttpResponse::Ok()
-            .content_type(ContentType::json())
-            .json(analysis),
+    let (result, status) = Analysis::within(budget::timeout_from_env(), bytes, hint, "remote_file");
+
+    if status == Status::Timeout {
+        log::warn!("analysis ran out of time, the result may be partial");
+    }
+
+    match result {
+        Ok(mut analysis) => {
+            if let Some(registry) = Registry::from_env() {
+                if let Err(e) = analysis.record_2ddoc(&registry, submitter) {
+                    log::error!("2D-Doc registry failed: {}", e);
+                }
+            }
+
+            if let Some(expected_holder) = expected_holder {
+                analysis.match_holder(expected_holder);
+            }
+
+            HttpResponse::Ok()
+                .content_type(ContentType::json())
+                .json(AnalysisResponse { analysis, status })
+        }
         Err(error_msg) => HttpResponse::UnprocessableEntity()
             .content_type(ContentType::json())
             .json(AnalysisError {
                 upstream_status_code: None,
                 upstream_body: None,
-                body: Some(error_msg),
+                body: Some(match status {
+                    Status::Timeout => format!("Analysis timed out: {}", error_msg),
+                    Status::Complete => error_msg,
+                }),
             }),
     }
 }
@@ -12,6 +12,12 @@ pub async fn main() -> std::io::Result<()> {
         .format(|buf, record| writeln!(buf, "{}", record.args()))
         .init();
 
+    // the missing programs are reported once, their steps are skipped
+    crate::tools::tools().log();
+
+    // the workers share the engine, loaded before the first request
+    crate::ocrs::warm_up();
+
     HttpServer::new(|| {
         App::new()
             .wrap(Logger::new(r#"{"timestamp":"%t","method":"%r","status":%s,"response_time":%D,"remote_addr":"%a","user_agent":"%{User-Agent}i","remote_file":"%{X-Remote-File}i"}"#))
@@ -0,0 +1,623 @@
+use ocrs::{TextItem, TextLine};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+
+// the positioned text of a document, whatever read it: the text layer of a pdf
+// (pdftotext -bbox-layout), tesseract (hocr) or ocrs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Layout {
+    pub pages: Vec<Page>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Page {
+    pub width: f32,
+    pub height: f32,
+    // from top to bottom
+    pub lines: Vec<Line>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Line {
+    // from left to right
+    pub words: Vec<Word>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Word {
+    pub text: String,
+    pub bbox: BBox,
+    // between 0 and 1, given by tesseract only
+    pub confidence: Option<f64>,
+}
+
+// in points for a pdf, in pixels for an image. y goes down
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BBox {
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+}
+
+// a gap between two words wider than this many line heights separates two columns
+const COLUMN_GAP: f32 = 1.5;
+// a gap between two lines taller than this many line heights ends a block
+const BLOCK_GAP: f32 = 2.0;
+
+impl BBox {
+    pub fn new(x0: f32, y0: f32, x1: f32, y1: f32) -> Self {
+        BBox { x0, y0, x1, y1 }
+    }
+
+    pub fn height(&self) -> f32 {
+        self.y1 - self.y0
+    }
+
+    pub fn union(&self, other: &BBox) -> BBox {
+        BBox {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
+
+    pub fn overlaps_horizontally(&self, other: &BBox) -> bool {
+        self.x0 < other.x1 && other.x0 < self.x1
+    }
+
+    pub fn overlaps_vertically(&self, other: &BBox) -> bool {
+        self.y0 < other.y1 && other.y0 < self.y1
+    }
+}
+
+impl Line {
+    pub fn bbox(&self) -> Option<BBox> {
+        self.words
+            .iter()
+            .map(|word| word.bbox)
+            .reduce(|a, b| a.union(&b))
+    }
+
+    pub fn text(&self) -> String {
+        self.words
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+
+    // the runs of words separated by a column gap
+    pub fn segments(&self) -> Vec<Line> {
+        let height = self.bbox().map(|bbox| bbox.height()).unwrap_or_default();
+        let mut segments: Vec<Line> = vec![];
+
+        for word in &self.words {
+            match segments.last_mut() {
+                Some(segment)
+                    if segment
+                        .words
+                        .last()
+                        .is_some_and(|last| word.bbox.x0 - last.bbox.x1 <= COLUMN_GAP * height) =>
+                {
+                    segment.words.push(word.clone())
+                }
+                _ => segments.push(Line {
+                    words: vec![word.clone()],
+                }),
+            }
+        }
+
+        segments
+    }
+}
+
+impl Page {
+    // the lines sorted from top to bottom and their words from left to right
+    pub fn new(width: f32, height: f32, mut lines: Vec<Line>) -> Self {
+        lines.retain(|line| !line.words.is_empty());
+        for line in lines.iter_mut() {
+            line.words.sort_by(|a, b| a.bbox.x0.total_cmp(&b.bbox.x0));
+        }
+        lines.sort_by(|a, b| {
+            let (a, b) = (a.bbox().unwrap(), b.bbox().unwrap());
+            a.y0.total_cmp(&b.y0).then(a.x0.total_cmp(&b.x0))
+        });
+
+        Page {
+            width,
+            height,
+            lines,
+        }
+    }
+
+    // the first segment of a line matching the regex
+    pub fn find(&self, regex: &Regex) -> Option<Line> {
+        self.lines
+            .iter()
+            .flat_map(|line| line.segments())
+            .find(|segment| regex.is_match(&segment.text()))
+    }
+
+    // the text on the right of the box, on the same line
+    pub fn right_of(&self, bbox: &BBox) -> Option<String> {
+        let words = self
+            .lines
+            .iter()
+            .flat_map(|line| line.words.iter())
+            .filter(|word| word.bbox.overlaps_vertically(bbox) && word.bbox.x0 >= bbox.x1)
+            .cloned()
+            .collect::<Vec<Word>>();
+
+        let first = Line { words }.segments().into_iter().next()?;
+        Some(first.text())
+    }
+
+    // the lines of the column of the box, below it, up to the end of the block
+    pub fn column_below(&self, bbox: &BBox, max_lines: usize) -> Vec<String> {
+        let mut column: Vec<String> = vec![];
+        let mut last = *bbox;
+
+        for line in &self.lines {
+            if column.len() >= max_lines {
+                break;
+            }
+
+            let Some(line_bbox) = line.bbox() else {
+                continue;
+            };
+            if line_bbox.y0 < last.y1 - last.height() / 2.0 {
+                continue;
+            }
+
+            let Some(segment) = line.segments().into_iter().find(|segment| {
+                segment
+                    .bbox()
+                    .is_some_and(|b| b.overlaps_horizontally(bbox))
+            }) else {
+                continue;
+            };
+            let segment_bbox = segment.bbox().unwrap();
+
+            if segment_bbox.y0 - last.y1 > BLOCK_GAP * segment_bbox.height() {
+                break;
+            }
+
+            column.push(segment.text());
+            last = segment_bbox;
+        }
+
+        column
+    }
+
+    // the lines side by side on the page, merged into rows from top to bottom
+    fn rows(&self) -> Vec<Line> {
+        let mut rows: Vec<Line> = vec![];
+
+        for line in &self.lines {
+            let line_bbox = line.bbox().unwrap();
+            let row = rows.iter_mut().find(|row| {
+                let row_bbox = row.bbox().unwrap();
+                let overlap = row_bbox.y1.min(line_bbox.y1) - row_bbox.y0.max(line_bbox.y0);
+                overlap > row_bbox.height().min(line_bbox.height()) / 2.0
+            });
+
+            match row {
+                Some(row) => row.words.extend(line.words.iter().cloned()),
+                None => rows.push(line.clone()),
+            }
+        }
+
+        for row in rows.iter_mut() {
+            row.words.sort_by(|a, b| a.bbox.x0.total_cmp(&b.bbox.x0));
+        }
+        rows
+    }
+
+    // the median width of a character, the unit of the columns of the text
+    fn char_width(&self) -> f32 {
+        let mut widths = self
+            .lines
+            .iter()
+            .flat_map(|line| line.words.iter())
+            .map(|word| (word.bbox.x1 - word.bbox.x0) / word.text.chars().count() as f32)
+            .filter(|width| *width > 0.0)
+            .collect::<Vec<f32>>();
+        widths.sort_by(|a, b| a.total_cmp(b));
+
+        widths.get(widths.len() / 2).copied().unwrap_or(1.0)
+    }
+
+    // as pdftotext -layout: each word at the column of its position, the columns
+    // separated by at least 3 spaces and the blocks by an empty line
+    pub fn to_layout_text(&self) -> String {
+        let char_width = self.char_width();
+        let mut text_lines: Vec<String> = vec![];
+        let mut last_row: Option<BBox> = None;
+
+        for row in self.rows() {
+            let row_bbox = row.bbox().unwrap();
+            if last_row.is_some_and(|last| row_bbox.y0 - last.y1 > row_bbox.height()) {
+                text_lines.push(String::new());
+            }
+
+            let mut text = String::new();
+            let mut last_word: Option<&Word> = None;
+
+            for word in &row.words {
+                let mut column = (word.bbox.x0 / char_width).round() as usize;
+                let len = text.chars().count();
+
+                if let Some(last) = last_word {
+                    let gap = if word.bbox.x0 - last.bbox.x1 > COLUMN_GAP * row_bbox.height() {
+                        3
+                    } else {
+                        1
+                    };
+                    column = column.max(len + gap);
+                }
+
+                text.push_str(&" ".repeat(column.saturating_sub(len)));
+                text.push_str(&word.text);
+                last_word = Some(word);
+            }
+
+            text_lines.push(text);
+            last_row = Some(row_bbox);
+        }
+
+        text_lines.join("\n")
+    }
+}
+
+impl Layout {
+    // the text rendered with its columns, for the heuristics written for pdftotext -layout
+    pub fn to_layout_text(&self) -> String {
+        self.pages
+            .iter()
+            .map(|page| page.to_layout_text())
+            .collect::<Vec<String>>()
+            .join("\x0c")
+    }
+
+    pub fn text(&self) -> String {
+        self.pages
+            .iter()
+            .map(|page| {
+                page.lines
+                    .iter()
+                    .map(|line| line.text())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            })
+            .collect::<Vec<String>>()
+            .join("\x0c")
+    }
+
+    // the xhtml of pdftotext -bbox-layout: page, flow, block, line and word elements
+    pub fn from_bbox_layout(xhtml: &str) -> Result<Self, String> {
+        let doc = Html::parse_document(xhtml);
+        let page_selector = Selector::parse("page").unwrap();
+        let line_selector = Selector::parse("line").unwrap();
+        let word_selector = Selector::parse("word").unwrap();
+
+        let pages = doc
+            .select(&page_selector)
+            .map(|page| {
+                let lines = page
+                    .select(&line_selector)
+                    .map(|line| Line {
+                        words: line
+                            .select(&word_selector)
+                            .filter_map(|word| {
+                                Some(Word {
+                                    text: text_of(&word)?,
+                                    bbox: bbox_from_attributes(&word)?,
+                                    confidence: None,
+                                })
+                            })
+                            .collect(),
+                    })
+                    .collect();
+
+                Page::new(attribute(&page, "width"), attribute(&page, "height"), lines)
+            })
+            .collect::<Vec<Page>>();
+
+        if pages.is_empty() {
+            return Err("no page in the bbox layout".to_string());
+        }
+
+        Ok(Layout { pages })
+    }
+
+    // the hocr of tesseract: ocr_page, ocr_line (or header, caption) and ocrx_word
+    // elements, their box and confidence in the title
+    pub fn from_hocr(hocr: &str) -> Result<Self, String> {
+        let doc = Html::parse_document(hocr);
+        let page_selector = Selector::parse(".ocr_page").unwrap();
+        let line_selector =
+            Selector::parse(".ocr_line, .ocr_header, .ocr_caption, .ocr_textfloat").unwrap();
+        let word_selector = Selector::parse(".ocrx_word").unwrap();
+
+        let pages = doc
+            .select(&page_selector)
+            .map(|page| {
+                let page_bbox = bbox_from_title(&page);
+                let lines = page
+                    .select(&line_selector)
+                    .map(|line| Line {
+                        words: line
+                            .select(&word_selector)
+                            .filter_map(|word| {
+                                Some(Word {
+                                    text: text_of(&word)?,
+                                    bbox: bbox_from_title(&word)?,
+                                    confidence: confidence_from_title(&word),
+                                })
+                            })
+                            .collect(),
+                    })
+                    .collect();
+
+                Page::new(
+                    page_bbox.map(|b| b.x1).unwrap_or_default(),
+                    page_bbox.map(|b| b.y1).unwrap_or_default(),
+                    lines,
+                )
+            })
+            .collect::<Vec<Page>>();
+
+        if pages.is_empty() {
+            return Err("no page in the hocr".to_string());
+        }
+
+        Ok(Layout { pages })
+    }
+
+    // the lines read by ocrs on an image of the given size
+    pub fn from_ocrs(text_lines: &[TextLine], width: u32, height: u32) -> Self {
+        let lines = text_lines
+            .iter()
+            .map(|line| Line {
+                words: line
+                    .words()
+                    .map(|word| {
+                        let rect = word.bounding_rect();
+                        Word {
+                            text: word.to_string(),
+                            bbox: BBox::new(
+                                rect.left() as f32,
+                                rect.top() as f32,
+                                rect.right() as f32,
+                                rect.bottom() as f32,
+                            ),
+                            confidence: None,
+                        }
+                    })
+                    .filter(|word| !word.text.trim().is_empty())
+                    .collect(),
+            })
+            .collect();
+
+        Layout {
+            pages: vec![Page::new(width as f32, height as f32, lines)],
+        }
+    }
+}
+
+fn text_of(el: &ElementRef) -> Option<String> {
+    let text = el.text().collect::<String>().trim().to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+// the html parser lowers the names of the attributes: xMin becomes xmin
+fn attribute(el: &ElementRef, name: &str) -> f32 {
+    el.value()
+        .attr(name)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
+
+fn bbox_from_attributes(el: &ElementRef) -> Option<BBox> {
+    let value = |name: &str| el.value().attr(name)?.parse::<f32>().ok();
+    Some(BBox::new(
+        value("xmin")?,
+        value("ymin")?,
+        value("xmax")?,
+        value("ymax")?,
+    ))
+}
+
+// ex: title="bbox 139 97 282 131; x_wconf 96"
+fn bbox_from_title(el: &ElementRef) -> Option<BBox> {
+    let title = el.value().attr("title")?;
+    let bbox = title
+        .split(';')
+        .find_map(|property| property.trim().strip_prefix("bbox "))?;
+    let coords = bbox
+        .split_whitespace()
+        .map(|s| s.parse::<f32>().ok())
+        .collect::<Option<Vec<f32>>>()?;
+
+    match coords[..] {
+        [x0, y0, x1, y1] => Some(BBox::new(x0, y0, x1, y1)),
+        _ => None,
+    }
+}
+
+fn confidence_from_title(el: &ElementRef) -> Option<f64> {
+    el.value()
+        .attr("title")?
+        .split(';')
+        .find_map(|property| property.trim().strip_prefix("x_wconf "))?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|conf| conf / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, x0: f32, y0: f32, x1: f32, y1: f32) -> Word {
+        Word {
+            text: text.to_string(),
+            bbox: BBox::new(x0, y0, x1, y1),
+            confidence: None,
+        }
+    }
+
+    // the holder on the right of its label, the address below, in points
+    fn holder_layout() -> Layout {
+        let lines = vec![
+            vec![
+                word("TITULAIRE", 50.0, 100.0, 110.0, 112.0),
+                word("DU", 113.0, 100.0, 130.0, 112.0),
+                word("COMPTE", 133.0, 100.0, 180.0, 112.0),
+                word("M", 200.0, 100.0, 210.0, 112.0),
+                word("MATISSE", 213.0, 100.0, 260.0, 112.0),
+                word("HENRI", 263.0, 100.0, 300.0, 112.0),
+            ],
+            vec![word("DOMICILIATION", 350.0, 100.0, 420.0, 112.0)],
+            vec![
+                word("18", 50.0, 114.0, 70.0, 126.0),
+                word("RUE", 73.0, 114.0, 100.0, 126.0),
+                word("CARNOT", 103.0, 114.0, 180.0, 126.0),
+            ],
+            vec![
+                word("92120", 50.0, 128.0, 90.0, 140.0),
+                word("MONTROUGE", 93.0, 128.0, 180.0, 140.0),
+            ],
+            vec![word("FOOTER", 50.0, 300.0, 180.0, 312.0)],
+        ];
+
+        Layout {
+            pages: vec![Page::new(
+                595.0,
+                842.0,
+                lines.into_iter().map(|words| Line { words }).collect(),
+            )],
+        }
+    }
+
+    const BBOX_LAYOUT: &str = r#"<html><body><doc>
+  <page width="595.000000" height="842.000000">
+    <flow>
+      <block xMin="50.0" yMin="100.0" xMax="180.0" yMax="140.0">
+        <line xMin="50.0" yMin="128.0" xMax="180.0" yMax="140.0">
+          <word xMin="93.0" yMin="128.0" xMax="180.0" yMax="140.0">MONTROUGE</word>
+          <word xMin="50.0" yMin="128.0" xMax="90.0" yMax="140.0">92120</word>
+        </line>
+        <line xMin="50.0" yMin="100.0" xMax="110.0" yMax="112.0">
+          <word xMin="50.0" yMin="100.0" xMax="110.0" yMax="112.0">TITULAIRE</word>
+          <word xMin="113.0" yMin="100.0" xMax="120.0" yMax="112.0"> </word>
+        </line>
+      </block>
+    </flow>
+  </page>
+</doc></body></html>"#;
+
+    const HOCR: &str = r#"<html><body>
+<div class='ocr_page' id='page_1' title='image "-"; bbox 0 0 1000 800; ppageno 0'>
+ <div class='ocr_carea' id='block_1_1' title="bbox 10 10 400 80">
+  <p class='ocr_par' id='par_1_1' title="bbox 10 10 400 80">
+   <span class='ocr_line' id='line_1_2' title="bbox 10 50 300 80; baseline 0 -5">
+    <span class='ocrx_word' id='word_1_3' title='bbox 10 50 100 80; x_wconf 91'>FR76</span>
+    <span class='ocrx_word' id='word_1_4' title='bbox 110 50 200 80; x_wconf 85'>3000</span>
+   </span>
+   <span class='ocr_header' id='line_1_1' title="bbox 10 10 400 40; baseline 0 -5">
+    <span class='ocrx_word' id='word_1_1' title='bbox 10 10 100 40; x_wconf 96'>IBAN</span>
+    <span class='ocrx_word' id='word_1_2' title='bbox 110 10 150 40; x_wconf 40'> </span>
+   </span>
+  </p>
+ </div>
+</div>
+</body></html>"#;
+
+    #[test]
+    fn test_from_bbox_layout() {
+        let layout = Layout::from_bbox_layout(BBOX_LAYOUT).unwrap();
+        let page = &layout.pages[0];
+
+        assert_eq!((page.width, page.height), (595.0, 842.0));
+        // sorted from top to bottom and from left to right, the empty words dropped
+        assert_eq!(layout.text(), "TITULAIRE\n92120 MONTROUGE");
+        assert_eq!(
+            page.lines[1].words[0].bbox,
+            BBox::new(50.0, 128.0, 90.0, 140.0)
+        );
+    }
+
+    #[test]
+    fn test_segments() {
+        let layout = holder_layout();
+
+        // the label and the holder are two columns of the same line
+        assert_eq!(
+            layout.pages[0].lines[0]
+                .segments()
+                .iter()
+                .map(|segment| segment.text())
+                .collect::<Vec<String>>(),
+            vec!["TITULAIRE DU COMPTE", "M MATISSE HENRI"]
+        );
+    }
+
+    #[test]
+    fn test_geometry() {
+        let layout = holder_layout();
+        let page = &layout.pages[0];
+
+        let label = page
+            .find(&Regex::new("TITULAIRE").unwrap())
+            .and_then(|segment| segment.bbox())
+            .unwrap();
+        assert_eq!(label, BBox::new(50.0, 100.0, 180.0, 112.0));
+        assert_eq!(page.right_of(&label).as_deref(), Some("M MATISSE HENRI"));
+
+        // the block ends before the footer, far below
+        assert_eq!(
+            page.column_below(&label, 5),
+            vec!["18 RUE CARNOT", "92120 MONTROUGE"]
+        );
+
+        let domiciliation = page
+            .find(&Regex::new("DOMICILIATION").unwrap())
+            .and_then(|segment| segment.bbox())
+            .unwrap();
+        assert!(page.column_below(&domiciliation, 5).is_empty());
+    }
+
+    #[test]
+    fn test_to_layout_text() {
+        let text = holder_layout().to_layout_text();
+        let lines = text.lines().collect::<Vec<&str>>();
+
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].trim().starts_with("TITULAIRE DU COMPTE   "));
+        assert!(lines[0].contains("M MATISSE HENRI"));
+        assert!(lines[0].ends_with("DOMICILIATION"));
+        // the address stays in the column of the label
+        assert_eq!(lines[1].find("18"), lines[0].find("TITULAIRE"));
+        assert_eq!(lines[3], "");
+    }
+
+    #[test]
+    fn test_from_hocr() {
+        let layout = Layout::from_hocr(HOCR).unwrap();
+        let page = &layout.pages[0];
+
+        assert_eq!((page.width, page.height), (1000.0, 800.0));
+        // sorted from top to bottom, the empty words dropped
+        assert_eq!(layout.text(), "IBAN\nFR76 3000");
+        assert_eq!(page.lines[1].words[1].confidence, Some(0.85));
+    }
+}
@@ -1,13 +1,19 @@
 pub mod analysis;
+pub mod budget;
 pub mod datamatrix;
+pub mod epc;
 pub mod fi_extract;
 pub mod file_utils;
 pub mod http;
 pub mod image_utils;
+pub mod layout;
+pub mod mandate;
 pub mod ocr;
+pub mod ocr_backend;
 pub mod ocrs;
 pub mod rib;
 pub mod shapes;
 pub mod tesseract;
 pub mod text;
+pub mod tools;
 pub mod twoddoc;
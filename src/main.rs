@@ -1,29 +1,109 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
 use la_taupe::{
     analysis::{Analysis, Hint, Type},
+    budget,
+    datamatrix::fetch_datamatrix,
+    fi_extract::{self, IbanToBankName},
+    file_utils::bytes_to_img,
     http::server,
-    twoddoc::trust_service,
+    twoddoc::{
+        data_structure::data_structure_from_json, decode, signature::CertificateInfo,
+        trust_service, verify,
+    },
 };
 use serde_json::json;
-use std::{env::args, path::Path};
+
+#[derive(Parser)]
+#[command(
+    name = "la_taupe",
+    about = "La taupe: a tool to analyze files",
+    long_about = "La taupe: a tool to analyze files.\nWithout argument, starts the server.",
+    disable_version_flag = true,
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    /// print the version
+    #[arg(long)]
+    version: bool,
+
+    /// print the trusted repository urls
+    #[arg(long)]
+    trusted_repositories_urls: bool,
+
+    /// type of the analyzed files: rib, 2ddoc, 2ddoc_cross_check, sepa_mandate
+    #[arg(long, value_parser = parse_type, default_value = "rib")]
+    hint: Type,
+
+    /// name of the person or company the RIB should belong to
+    #[arg(long)]
+    expected_holder: Option<String>,
+
+    /// files to analyze
+    files: Vec<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// decode a raw 2D-Doc string, without checking its signature
+    Decode { doc: String },
+    /// print the signature and certificate chain verification of the 2D-Doc of a file
+    Verify { file: PathBuf },
+    /// inspect the trust service list
+    Tsl {
+        #[command(subcommand)]
+        command: TslCommand,
+    },
+    /// browse the description of the 2D-Doc fields
+    Fields { id: Option<String> },
+    /// manage the reference data of the banks
+    Banks {
+        #[command(subcommand)]
+        command: BanksCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum BanksCommand {
+    /// convert the raw ECB list of financial institutions (utf-16 csv) to the bundled csv
+    Import {
+        file: PathBuf,
+        /// keep only the institutions of this country, ex: FR
+        #[arg(long)]
+        country: Option<String>,
+    },
+    /// print the institution of an iban
+    Show {
+        iban: String,
+        /// raw ECB csv to use instead of the bundled data
+        #[arg(long)]
+        mfi: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TslCommand {
+    /// list the authorities with their certificates
+    List,
+}
+
+fn parse_type(s: &str) -> Result<Type, String> {
+    serde_json::from_value(json!(s)).map_err(|_| format!("unknown type: {}", s))
+}
 
 fn main() {
-    let args: Vec<String> = args().collect();
-
-    if args.contains(&String::from("--help")) {
-        println!("La taupe: a tool to analyze files");
-        println!("--version to print the version");
-        println!("--trusted-repositories-urls to print the trusted repository urls");
-        println!("la_taupe file_path to analyze a file");
-        println!("la_taupe to start the server");
-        std::process::exit(0);
-    }
+    let cli = Cli::parse();
 
-    if args.contains(&String::from("--version")) {
+    if cli.version {
         println!("Version: {}", env!("GIT_HASH"));
         std::process::exit(0);
     }
 
-    if args.contains(&String::from("--trusted-repositories-urls")) {
+    if cli.trusted_repositories_urls {
         let urls = trust_service::trusted_repositories_urls();
         for url in urls {
             println!("{}", url);
@@ -31,30 +111,148 @@ fn main() {
         std::process::exit(0);
     }
 
-    if args.len() == 1 {
-        let _ = server::main();
-    } else {
-        env_logger::init();
-
-        let paths: Vec<&Path> = args[1..].iter().map(Path::new).collect();
-        paths.iter().for_each(|path| {
-            let result = Analysis::try_from((*path, Some(Hint::Type(Type::Rib))));
-            match result {
-                Ok(analysis_result) => {
-                    let json = json!({
-                        "file_path": path.to_str().unwrap(),
-                        "analysis": analysis_result
-                    });
-                    println!("{}", serde_json::to_string_pretty(&json).unwrap());
-                }
-                Err(msg) => {
-                    let json = json!({
-                        "file_path": path.to_str().unwrap(),
-                        "error": msg
-                    });
-                    println!("{}", serde_json::to_string_pretty(&json).unwrap());
+    match cli.command {
+        Some(Command::Decode { doc }) => decode_command(&doc),
+        Some(Command::Verify { file }) => verify_command(&file),
+        Some(Command::Tsl {
+            command: TslCommand::List,
+        }) => tsl_list_command(),
+        Some(Command::Fields { id }) => fields_command(id.as_deref()),
+        Some(Command::Banks {
+            command: BanksCommand::Import { file, country },
+        }) => banks_import_command(&file, country.as_deref()),
+        Some(Command::Banks {
+            command: BanksCommand::Show { iban, mfi },
+        }) => banks_show_command(&iban, mfi.as_deref()),
+        None if cli.files.is_empty() => {
+            let _ = server::main();
+        }
+        None => {
+            env_logger::init();
+            analyze_command(&cli.files, cli.hint, cli.expected_holder.as_deref());
+        }
+    }
+}
+
+fn print_json(value: &serde_json::Value) {
+    println!("{}", serde_json::to_string_pretty(value).unwrap());
+}
+
+fn exit_with_error(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(1);
+}
+
+fn analyze_command(paths: &[PathBuf], hint: Type, expected_holder: Option<&str>) {
+    paths.iter().for_each(|path| {
+        let (result, status) = budget::with_budget(budget::timeout_from_env(), || {
+            Analysis::try_from((path.as_path(), Some(Hint::Type(hint))))
+        });
+        match result {
+            Ok(mut analysis_result) => {
+                if let Some(expected_holder) = expected_holder {
+                    analysis_result.match_holder(expected_holder);
                 }
+                print_json(&json!({
+                    "file_path": path.to_str().unwrap(),
+                    "analysis": analysis_result,
+                    "status": status
+                }))
             }
-        });
+            Err(msg) => print_json(&json!({
+                "file_path": path.to_str().unwrap(),
+                "error": msg,
+                "status": status
+            })),
+        }
+    });
+}
+
+fn decode_command(doc: &str) {
+    match decode(doc) {
+        Some(ddoc) => print_json(&json!({
+            "2ddoc": ddoc,
+            "signature": ddoc.signature,
+        })),
+        None => exit_with_error("Failed to decode the 2D-Doc"),
+    }
+}
+
+fn verify_command(path: &Path) {
+    let content = std::fs::read(path)
+        .unwrap_or_else(|e| exit_with_error(&format!("Failed to read file: {}", e)));
+    let img = bytes_to_img(content).unwrap_or_else(|e| exit_with_error(&e));
+
+    let doc =
+        fetch_datamatrix(img).unwrap_or_else(|| exit_with_error("No 2D-Doc found in the file"));
+
+    match verify(&doc) {
+        Some(verification) => print_json(&json!(verification)),
+        None => exit_with_error("Failed to decode the 2D-Doc"),
+    }
+}
+
+fn tsl_list_command() {
+    let authorities = trust_service::trust_services()
+        .iter()
+        .map(|service| {
+            let certificates = service
+                .ca_certificates()
+                .iter()
+                .map(CertificateInfo::from)
+                .collect::<Vec<CertificateInfo>>();
+
+            json!({
+                "autorite_certification": service.trade_name,
+                "information_url": service.information_url.to_string(),
+                "certificates": certificates,
+            })
+        })
+        .collect::<Vec<serde_json::Value>>();
+
+    print_json(&json!(authorities));
+}
+
+fn fields_command(id: Option<&str>) {
+    let structures = data_structure_from_json();
+
+    match id {
+        Some(id) => {
+            let structure = structures
+                .iter()
+                .find(|s| s.id.eq_ignore_ascii_case(id))
+                .unwrap_or_else(|| exit_with_error(&format!("Unknown field: {}", id)));
+
+            println!("{} - {}", structure.id, structure.nom);
+            println!("nature: {}", structure.nature);
+            println!("taille min: {}", structure.min);
+            if structure.max == usize::MAX {
+                println!("taille max: aucune");
+            } else {
+                println!("taille max: {}", structure.max);
+            }
+            println!("{}", structure.description);
+        }
+        None => structures
+            .iter()
+            .for_each(|s| println!("{}  {}", s.id, s.nom)),
+    }
+}
+
+fn banks_import_command(path: &Path, country: Option<&str>) {
+    let reference = IbanToBankName::from_mfi_file(path.to_str().unwrap())
+        .unwrap_or_else(|e| exit_with_error(&e));
+
+    print!("{}", reference.to_bundled_csv(country));
+}
+
+fn banks_show_command(iban: &str, mfi: Option<&Path>) {
+    if let Some(mfi) = mfi {
+        fi_extract::import_mfi_file(mfi.to_str().unwrap()).unwrap_or_else(|e| exit_with_error(&e));
+    }
+
+    match fi_extract::institution(iban) {
+        Some(institution) => print_json(&json!(institution)),
+        None => exit_with_error(&format!("Unknown bank for {}", iban)),
     }
 }
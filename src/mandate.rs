@@ -0,0 +1,214 @@
+use std::sync::LazyLock;
+
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::text::{
+    column::{column, segments},
+    date::find_date,
+};
+
+// a SEPA direct debit mandate, or an invoice quoting it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Mandate {
+    // référence unique du mandat, up to 35 characters
+    pub rum: Option<String>,
+    // identifiant créancier SEPA, ex: FR72ZZZ123456
+    pub ics: Option<String>,
+    // the check digits of the ics are right
+    pub ics_valid: Option<bool>,
+    pub creditor: Option<String>,
+    pub signature_date: Option<NaiveDate>,
+}
+
+static RUM_LABEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(r[ée]f[ée]rence unique d[ue] mandat|r[ée]f[ée]rence d[ue] mandat|mandate reference|\bRUM\b)\s*(\(RUM\))?\s*:?").unwrap()
+});
+static ICS_LABEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(identifiant (du )?cr[ée]ancier( sepa)?|creditor identifier|creditor id|\bI\.?C\.?S\b)\s*(\(ICS\))?\s*:?").unwrap()
+});
+static CREDITOR_LABEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)(nom du cr[ée]ancier|raison sociale du cr[ée]ancier|d[ée]signation du cr[ée]ancier|creditor name|^\s*cr[ée]ancier)\s*:?").unwrap()
+});
+// a bare date label is the one of an invoice, not of the signature
+static DATE_LABEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(date de signature|sign[ée] le|fait [àa] .{0,40}?le|signature date)").unwrap()
+});
+
+// the rum may contain letters, digits and + ? / - : ( ) . , ' and spaces
+static RUM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z0-9+?/\-:().,' ]{1,35}$").unwrap());
+// country, check digits, creditor business code, national identifier
+static ICS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Z]{2}\d{2}[A-Z0-9]{3}[A-Z0-9]{1,28}$").unwrap());
+// without label, the ics with the default business code ZZZ
+static ICS_ZZZ: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[A-Z]{2}\d{2} ?ZZZ ?[A-Z0-9]{1,28}\b").unwrap());
+
+impl Mandate {
+    pub fn parse(text: &str) -> Option<Self> {
+        let rum = labeled_values(text, &RUM_LABEL)
+            .into_iter()
+            // a reference has at least one digit, unlike the following label
+            .find(|value| RUM.is_match(value) && value.chars().any(|c| c.is_ascii_digit()));
+
+        let ics = find_ics(text);
+        let ics_valid = ics.as_deref().map(is_valid_ics);
+
+        if rum.is_none() && ics.is_none() {
+            return None;
+        }
+
+        let creditor = labeled_values(text, &CREDITOR_LABEL)
+            .into_iter()
+            .find(|value| {
+                value.chars().any(|c| c.is_alphabetic())
+                    && !ICS.is_match(&value.replace(" ", ""))
+                    && !ICS_LABEL.is_match(value)
+            });
+
+        let signature_date = find_signature_date(text);
+
+        Some(Mandate {
+            rum,
+            ics,
+            ics_valid,
+            creditor,
+            signature_date,
+        })
+    }
+}
+
+// the labelled ics with right check digits first
+fn find_ics(text: &str) -> Option<String> {
+    let mut found = labeled_values(text, &ICS_LABEL)
+        .into_iter()
+        .map(|value| value.replace(" ", "").to_uppercase())
+        .filter(|value| ICS.is_match(value))
+        .collect::<Vec<String>>();
+
+    found.extend(ICS_ZZZ.find_iter(text).map(|m| m.as_str().replace(" ", "")));
+
+    found
+        .iter()
+        .find(|ics| is_valid_ics(ics))
+        .or(found.first())
+        .cloned()
+}
+
+// as for an iban, once the business code is removed:
+// the national identifier, the country and the check digits must give 1 modulo 97
+pub fn is_valid_ics(ics: &str) -> bool {
+    let ics = ics.replace(" ", "").to_uppercase();
+    if !ICS.is_match(&ics) {
+        return false;
+    }
+
+    format!("{}{}", &ics[7..], &ics[..4])
+        .chars()
+        .try_fold(0u32, |acc, c| {
+            let value = c.to_digit(36)?;
+            let shift = if value < 10 { 10 } else { 100 };
+            Some((acc * shift + value) % 97)
+        })
+        == Some(1)
+}
+
+fn find_signature_date(text: &str) -> Option<NaiveDate> {
+    DATE_LABEL.find_iter(text).find_map(|m| {
+        let rest = &text[m.end()..];
+        let following = rest.lines().take(2).collect::<Vec<&str>>().join(" ");
+        find_date(&following)
+    })
+}
+
+// the text following each label on its line, or else below the label
+// on the next non empty line of its column
+fn labeled_values(text: &str, label: &Regex) -> Vec<String> {
+    label
+        .find_iter(text)
+        .filter_map(|m| {
+            let start = column(text, m.start());
+            let end = column(text, m.end());
+
+            let mut lines = text[m.end()..].lines();
+            let same_line = lines
+                .next()
+                .and_then(|rest| segments(rest).into_iter().next())
+                .map(|(_, _, segment)| segment.trim_start_matches(':').trim().to_string())
+                .filter(|segment| !segment.is_empty());
+
+            same_line.or_else(|| {
+                lines.find_map(|line| {
+                    segments(line)
+                        .into_iter()
+                        .find(|(s, e, _)| *s < end && *e > start)
+                        .map(|(_, _, segment)| segment)
+                })
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANDATE: &str = "                      Mandat de prélèvement SEPA
+
+Référence unique du mandat (RUM) : ++AM-2024-00042
+Identifiant créancier SEPA : FR72 ZZZ 123456
+
+En signant ce formulaire de mandat, vous autorisez Ateliers Matisse à envoyer des instructions
+à votre banque pour débiter votre compte.
+
+Nom du débiteur : Frida Kahlo                 Nom du créancier :
+IBAN : FR27 1820 6000 3060 0018 2530 127      Ateliers Matisse
+                                              18 rue Sadi Carnot
+                                              92120 Montrouge
+
+Fait à Paris, le 12 mars 2024                 Signature :
+";
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Mandate::parse(MANDATE),
+            Some(Mandate {
+                rum: Some("++AM-2024-00042".to_string()),
+                ics: Some("FR72ZZZ123456".to_string()),
+                ics_valid: Some(true),
+                creditor: Some("Ateliers Matisse".to_string()),
+                signature_date: NaiveDate::from_ymd_opt(2024, 3, 12),
+            })
+        );
+    }
+
+    #[test]
+    fn test_invoice() {
+        let text = "FACTURE 2024-118
+Prélèvement SEPA le 05/04/2024, RUM: EDF123456789   ICS: FR44ZZZ123456
+Date : 28/03/2024
+";
+        let mandate = Mandate::parse(text).unwrap();
+        assert_eq!(mandate.rum, Some("EDF123456789".to_string()));
+        assert_eq!(mandate.ics, Some("FR44ZZZ123456".to_string()));
+        assert_eq!(mandate.ics_valid, Some(false));
+        assert_eq!(mandate.creditor, None);
+        // the date of the invoice is not the one of the signature
+        assert_eq!(mandate.signature_date, None);
+
+        let rib = std::fs::read_to_string("tests/fixtures/rib/lcl.txt").unwrap();
+        assert_eq!(Mandate::parse(&rib), None);
+    }
+
+    #[test]
+    fn test_is_valid_ics() {
+        assert!(is_valid_ics("FR72ZZZ123456"));
+        assert!(is_valid_ics("DE98ZZZ09999999999"));
+        assert!(is_valid_ics("fr72 zzz 123456"));
+        assert!(!is_valid_ics("FR44ZZZ123456"));
+        assert!(!is_valid_ics("FR72ZZZ"));
+    }
+}
@@ -1,212 +1,447 @@
-use std::io::Cursor;
+use std::{io::Cursor, sync::LazyLock};
 
 use image::{DynamicImage, ImageDecoder, ImageReader};
 use log::trace;
-use ocrs::TextLine;
 use regex::Regex;
 
 use crate::{
+    budget,
     image_utils::{clean_image, only_rotate, resize, rotate, save_image_in_debug},
-    ocrs::{extract_anchors, image_to_string_using_ocrs, ocrs_anchors},
-    rib::{extract_fr_bic, extract_iban, Rib},
-    tesseract::{img_to_string_using_tesseract, tess_analyze},
-    text::simple_titulaire::find_simple_titulaire,
+    layout::Layout,
+    ocr_backend::{backends_from_env, OcrBackend},
+    rib::{
+        extract_iban_candidates,
+        provenance::{FieldProvenance, Provenance, Region, Source},
+        sepa::iban_anchor_pattern,
+        statement::Statement,
+        IbanCandidate, IbanSource, Rib,
+    },
+    shapes::{Anchor, Point},
+    tesseract::tess_analyze,
+    text::{address::find_titulaire_addr, simple_titulaire::find_simple_titulaire},
 };
 
 const OPTIMAL_TESSERACT_HEIGHT: u32 = 30;
 
-pub fn image_bytes_to_rib(content: Vec<u8>, name: &str) -> Option<Rib> {
-    let img = bytes_to_img(content)?;
-    save_image_in_debug(&img, name, "");
+// the order of the backends, see ocr_backend.rs
+const DEFAULT_PAGE_BACKENDS: &str = "ocrs,tesseract";
+const DEFAULT_CROP_BACKENDS: &str = "tesseract,ocrs";
+
+static PIPELINE: LazyLock<Pipeline> = LazyLock::new(Pipeline::from_env);
 
-    zoom_and_extract(&img, name).or_else(|| {
-        let cleaned_img = clean_image(&img, name);
-        zoom_and_extract(&cleaned_img, name)
-    })
+pub struct Pipeline {
+    // the strategies tried in turn on the full page
+    pub page: Vec<Box<dyn OcrBackend>>,
+    // the engines tried in turn to read the cropped iban
+    pub crop: Vec<Box<dyn OcrBackend>>,
 }
 
-pub fn zoom_and_extract(img: &DynamicImage, name: &str) -> Option<Rib> {
-    let mut rib = None;
-    let iban_regex = Regex::new(r"(?:^|\s)FR[\dO]").unwrap();
+impl Pipeline {
+    pub fn new(page: Vec<Box<dyn OcrBackend>>, crop: Vec<Box<dyn OcrBackend>>) -> Self {
+        Pipeline { page, crop }
+    }
 
-    let (ocrs_text, text_lines, maybe_anchors) = ocrs_anchors(img, &iban_regex, None);
-    let maybe_anchor = maybe_anchors.first();
+    // LA_TAUPE_OCR_PAGE_BACKENDS and LA_TAUPE_OCR_CROP_BACKENDS, ex: "ocrs" to run without tesseract
+    pub fn from_env() -> Self {
+        Pipeline {
+            page: backends_from_env("LA_TAUPE_OCR_PAGE_BACKENDS", DEFAULT_PAGE_BACKENDS),
+            crop: backends_from_env("LA_TAUPE_OCR_CROP_BACKENDS", DEFAULT_CROP_BACKENDS),
+        }
+    }
 
-    if let Some(iban) = extract_iban(&ocrs_text) {
-        trace!("early returns from ocrs for: {}", name);
-        let bic = extract_fr_bic(&ocrs_text);
-        let titulaire = zoom_and_extract_titulaire(img, text_lines, name);
-        return Some(Rib::from_iban(iban, titulaire, bic));
-    };
+    pub fn image_bytes_to_rib(&self, content: Vec<u8>, name: &str) -> Option<Rib> {
+        let img = bytes_to_img(content)?;
+        save_image_in_debug(&img, name, "");
 
-    if let Some(anchor) = maybe_anchor {
-        trace!("ocrs anchor found");
+        self.zoom_and_extract(&img, name, false).or_else(|| {
+            if budget::exhausted() {
+                return None;
+            }
 
-        let iban_image = crop(img, anchor.iban_mask(), name, "mask");
-        let iban = extract_iban_in_image(&iban_image, name);
+            let cleaned_img = clean_image(&img, name);
+            self.zoom_and_extract(&cleaned_img, name, true)
+        })
+    }
 
-        if let Some(iban) = iban {
-            let titulaire = zoom_and_extract_titulaire(img, text_lines.clone(), name);
-            let bic = extract_fr_bic(&ocrs_text);
-            rib = Some(Rib::from_iban(iban, titulaire, bic));
-        } else {
-            // maybe this is a long iban with some | between words
-            let iban_image = crop(img, anchor.narrow_iban_mask(), name, "narrow_mask");
-            let iban = extract_iban_in_image(&iban_image, name);
+    // the text of the page, read by the first engine of the page
+    pub fn image_bytes_to_text(&self, content: Vec<u8>) -> Option<String> {
+        let img = bytes_to_img(content)?;
+        let backend = self.page.first()?;
 
-            if let Some(iban) = iban {
-                let titulaire = zoom_and_extract_titulaire(img, text_lines, name);
-                let bic = extract_fr_bic(&ocrs_text);
-                rib = Some(Rib::from_iban(iban, titulaire, bic));
-            }
-        }
+        Some(backend.recognize(&img).text)
     }
 
-    if rib.is_some() {
-        return rib;
+    pub fn zoom_and_extract(&self, img: &DynamicImage, name: &str, cleaned: bool) -> Option<Rib> {
+        self.page.iter().find_map(|backend| {
+            if budget::exhausted() {
+                trace!("no time left for {} on {}", backend.name(), name);
+                return None;
+            }
+
+            trace!("{} on the page of {}", backend.name(), name);
+            backend.extract_page(self, img, name, cleaned)
+        })
     }
 
-    let (_hocr_string, maybe_angle, maybe_anchor) = tess_analyze(img);
+    fn extract_iban_in_image(
+        &self,
+        cropped_img: &DynamicImage,
+        name: &str,
+        region: Region,
+        cleaned: bool,
+    ) -> Option<(Vec<IbanCandidate>, FieldProvenance)> {
+        let mut texts = vec![];
+
+        for backend in &self.crop {
+            if budget::exhausted() {
+                break;
+            }
 
-    let (img, maybe_anchor) = maybe_angle
-        .map(|angle| {
-            let rotated_img = rotate(img, angle);
-            let (_, _, new_anchor) = tess_analyze(&rotated_img);
-            (rotated_img, new_anchor)
-        })
-        .unwrap_or((img.clone(), maybe_anchor));
+            let recognition = backend.recognize(cropped_img);
+            let candidates = extract_iban_candidates(&recognition.text, backend.source());
+            if !candidates.is_empty() {
+                let provenance =
+                    FieldProvenance::new(backend.source().into(), Some(region), cleaned)
+                        .with_engine_confidence(recognition.confidence);
+                return Some((candidates, provenance));
+            };
 
-    if let Some(anchor) = maybe_anchor {
-        trace!("tess anchor found");
+            texts.push(format!("{}: {}", backend.name(), recognition.text));
+        }
 
-        let iban_image = crop(&img, anchor.iban_mask(), name, "mask");
+        log::trace!("not found for {}: {}", name, texts.join(", "));
 
-        let iban_image = only_rotate(&iban_image, name);
-        let iban_image = resize(&iban_image, anchor.height, OPTIMAL_TESSERACT_HEIGHT);
-        save_image_in_debug(&iban_image, name, "rotated_resized_mask");
+        None
+    }
 
-        let iban = extract_iban_in_image(&iban_image, name);
-        if let Some(iban) = iban {
-            let (ocrs_text, text_lines, _) = ocrs_anchors(&img, &iban_regex, None);
-            let titulaire = zoom_and_extract_titulaire(&img, text_lines, name);
-            let bic = extract_fr_bic(&ocrs_text);
-            rib = Some(Rib::from_iban(iban, titulaire, bic));
+    // the address blocks above a postal code, else the blocks of a titulaire label,
+    // read again by the engines of the crops
+    fn zoom_and_extract_titulaire(
+        &self,
+        img: &DynamicImage,
+        layout: &Layout,
+        name: &str,
+        cleaned: bool,
+    ) -> Option<(Vec<String>, FieldProvenance)> {
+        if budget::exhausted() {
+            return None;
         }
-    }
 
-    rib
-}
+        let code_postal_line_regex = Regex::new(r"[[:space:]]*\d{5}\s+[[:alpha:]]").unwrap();
+        let code_postal_word_regex = Regex::new(r"^\d{5}").unwrap();
 
-fn zoom_and_extract_titulaire(
-    img: &DynamicImage,
-    text_lines: Vec<TextLine>,
-    name: &str,
-) -> Option<Vec<String>> {
-    let code_postal_line_regex = Regex::new(r"[[:space:]]*\d{5}\s+[[:alpha:]]").unwrap();
-    let code_postal_word_regex = Regex::new(r"^\d{5}").unwrap();
-
-    fn match_civilite(s: &str) -> bool {
-        let civilite =
-            Regex::new(r"(?i)(^|\s)(m|monsieur|mr|mademoiselle|ml|mle|mlle|melle|madame|mme)\.?\s")
-                .unwrap();
-        let prenom_nom_ou =
-            Regex::new(r"[[:upper:]]+ +[[:upper:]]+ +OU +[[:upper:]]+ +[[:upper:]]+").unwrap();
-
-        civilite.is_match(s) || prenom_nom_ou.is_match(s)
-    }
-
-    fn find_civilite(s: &str) -> Option<usize> {
-        let civilite =
-            Regex::new(r"(?i)(^|\s)(m|monsieur|mr|mademoiselle|ml|mle|mlle|melle|madame|mme)\.?\s")
-                .unwrap();
-        let prenom_nom_ou =
-            Regex::new(r"[[:upper:]]+ +[[:upper:]]+ +OU +[[:upper:]]+ +[[:upper:]]+").unwrap();
-        if civilite.is_match(s) {
-            civilite.find(s).map(|m| m.start())
-        } else if prenom_nom_ou.is_match(s) {
-            prenom_nom_ou.find(s).map(|m| m.start())
-        } else {
-            None
-        }
-    }
+        fn match_civilite(s: &str) -> bool {
+            let civilite = Regex::new(
+                r"(?i)(^|\s)(m|monsieur|mr|mademoiselle|ml|mle|mlle|melle|madame|mme)\.?\s",
+            )
+            .unwrap();
+            let prenom_nom_ou =
+                Regex::new(r"[[:upper:]]+ +[[:upper:]]+ +OU +[[:upper:]]+ +[[:upper:]]+").unwrap();
 
-    let postal_anchors = extract_anchors(
-        text_lines,
-        &code_postal_word_regex,
-        Some(&code_postal_line_regex),
-    );
+            civilite.is_match(s) || prenom_nom_ou.is_match(s)
+        }
 
-    let titulaires = postal_anchors
-        .iter()
-        .enumerate()
-        .map(|(index, anchor)| {
-            let cropped_img = crop(
-                img,
-                anchor.addr_mask(),
-                name,
-                &format!(r#"{}_addr_mask"#, index),
-            );
-            (index, image_to_string_using_ocrs(cropped_img), anchor)
-        })
-        .filter_map(|(index, text, anchor)| {
-            if match_civilite(&text) {
-                Some(text)
+        fn find_civilite(s: &str) -> Option<usize> {
+            let civilite = Regex::new(
+                r"(?i)(^|\s)(m|monsieur|mr|mademoiselle|ml|mle|mlle|melle|madame|mme)\.?\s",
+            )
+            .unwrap();
+            let prenom_nom_ou =
+                Regex::new(r"[[:upper:]]+ +[[:upper:]]+ +OU +[[:upper:]]+ +[[:upper:]]+").unwrap();
+            if civilite.is_match(s) {
+                civilite.find(s).map(|m| m.start())
+            } else if prenom_nom_ou.is_match(s) {
+                prenom_nom_ou.find(s).map(|m| m.start())
             } else {
+                None
+            }
+        }
+
+        let postal_anchors = anchors(
+            layout,
+            &code_postal_word_regex,
+            Some(&code_postal_line_regex),
+        );
+
+        let titulaires = postal_anchors
+            .iter()
+            .enumerate()
+            .filter_map(|(index, anchor)| {
+                let cropped_img = crop(
+                    img,
+                    anchor.addr_mask(),
+                    name,
+                    &format!(r#"{}_addr_mask"#, index),
+                );
+                if let Some((text, source)) = self.read_crop(&cropped_img, match_civilite) {
+                    return Some((text, source, Region::AddressMask));
+                }
+
                 let cropped_img = crop(
                     img,
                     anchor.right_align_addr_mask(),
                     name,
                     &format!(r#"{}_right_align_addr_mask"#, index),
                 );
-                let new_text = image_to_string_using_ocrs(cropped_img);
+                self.read_crop(&cropped_img, match_civilite)
+                    .map(|(text, source)| (text, source, Region::RightAlignAddressMask))
+            })
+            .map(|(text, source, region)| {
+                // on supprime tout ce qui se situe avant civilite
+                let start = find_civilite(&text).unwrap();
+                let text = text[start..].trim().to_string();
+
+                // on supprime toutes les lignes situées après le code postal
+                let lines: Vec<&str> = text.lines().collect();
+                let code_postal_index = lines
+                    .iter()
+                    .position(|line| code_postal_line_regex.is_match(line))
+                    .unwrap_or(lines.len() - 1);
+                (lines[..code_postal_index + 1].join("\n"), source, region)
+            })
+            .collect::<Vec<(String, IbanSource, Region)>>();
+
+        let titulaire = titulaires.first().map(|(s, source, region)| {
+            (
+                s.lines().map(|l| l.to_string()).collect(),
+                FieldProvenance::new((*source).into(), Some(*region), cleaned),
+            )
+        });
+
+        if titulaire.is_some() || budget::exhausted() {
+            return titulaire;
+        }
 
-                if match_civilite(&new_text) {
-                    Some(new_text)
-                } else {
-                    None
-                }
+        let titulaire_word_regex = Regex::new(r"(?i)titulaire").unwrap();
+
+        anchors(layout, &titulaire_word_regex, None)
+            .iter()
+            .enumerate()
+            .find_map(|(index, anchor)| {
+                let cropped_img = crop(
+                    img,
+                    anchor.titulaire_mask(),
+                    name,
+                    &format!(r#"{}_titulaire_mask"#, index),
+                );
+                let (text, source) =
+                    self.read_crop(&cropped_img, |text| titulaire_word_regex.is_match(text))?;
+                let titulaire = find_simple_titulaire(&text, 1)?;
+                Some((
+                    titulaire,
+                    FieldProvenance::new(source.into(), Some(Region::TitulaireMask), cleaned),
+                ))
+            })
+    }
+
+    // the text of the first engine of the crops that reads it as expected
+    fn read_crop(
+        &self,
+        cropped_img: &DynamicImage,
+        expected: impl Fn(&str) -> bool,
+    ) -> Option<(String, IbanSource)> {
+        self.crop.iter().find_map(|backend| {
+            if budget::exhausted() {
+                return None;
             }
+
+            let text = backend.recognize(cropped_img).text;
+            expected(&text).then(|| (text, backend.source()))
         })
-        .map(|text| {
-            // on supprime tout ce qui se situe avant civilite
-            let start = find_civilite(&text).unwrap();
-            let text = text[start..].trim().to_string();
-
-            // on supprime toutes les lignes situées après le code postal
-            let lines: Vec<&str> = text.lines().collect();
-            let code_postal_index = lines
-                .iter()
-                .position(|line| code_postal_line_regex.is_match(line))
-                .unwrap();
-            lines[..code_postal_index + 1].join("\n")
+    }
+}
+
+// at least one engine can read the page
+pub fn is_available() -> bool {
+    !PIPELINE.page.is_empty()
+}
+
+pub fn image_bytes_to_rib(content: Vec<u8>, name: &str) -> Option<Rib> {
+    PIPELINE.image_bytes_to_rib(content, name)
+}
+
+pub fn image_bytes_to_text(content: Vec<u8>) -> Option<String> {
+    PIPELINE.image_bytes_to_text(content)
+}
+
+pub fn zoom_and_extract(img: &DynamicImage, name: &str, cleaned: bool) -> Option<Rib> {
+    PIPELINE.zoom_and_extract(img, name, cleaned)
+}
+
+// the ibans of the whole text, else the iban masks around the anchors of the layout.
+// the layout also anchors the masks of the titulaire
+pub(crate) fn extract_page(
+    pipeline: &Pipeline,
+    img: &DynamicImage,
+    page: (&str, Option<&Layout>),
+    source: IbanSource,
+    name: &str,
+    cleaned: bool,
+) -> Option<Rib> {
+    let (text, layout) = page;
+    // the lines put back in their columns, as pdftotext -layout would
+    let layout_text = layout
+        .map(|layout| layout.to_layout_text())
+        .unwrap_or_else(|| text.to_string());
+
+    // the bic is always looked for in the text of the full page
+    let full_page = FieldProvenance::new(source.into(), Some(Region::FullPage), cleaned);
+    let titulaire = || {
+        titulaire_in_layout(&layout_text, source.into(), cleaned).or_else(|| {
+            layout
+                .and_then(|layout| pipeline.zoom_and_extract_titulaire(img, layout, name, cleaned))
         })
-        .collect::<Vec<String>>();
+    };
+
+    // the ibans of the transactions are not the account of the holder
+    if Statement::detect(&layout_text) {
+        trace!("statement read on the page of {}", name);
+        return Rib::parse_statement(layout_text, source, full_page);
+    }
+
+    let candidates = extract_iban_candidates(text, source);
+    if !candidates.is_empty() {
+        trace!("early returns from the text of the page for: {}", name);
+        let titulaire = titulaire();
+        let provenance = provenance(full_page.clone(), full_page, titulaire.as_ref());
+        return Rib::from_candidates(
+            candidates,
+            titulaire.map(|(t, _)| t),
+            &layout_text,
+            provenance,
+        );
+    };
+
+    let iban_regex = Regex::new(&iban_anchor_pattern()).unwrap();
+    let anchors = anchors(layout?, &iban_regex, None);
+    let anchor = anchors.first()?;
+    trace!("iban anchor found");
+
+    let iban_image = crop(img, anchor.iban_mask(), name, "mask");
+    let (candidates, iban_provenance) = pipeline
+        .extract_iban_in_image(&iban_image, name, Region::IbanMask, cleaned)
+        .or_else(|| {
+            if budget::exhausted() {
+                return None;
+            }
+
+            // maybe this is a long iban with some | between words
+            let iban_image = crop(img, anchor.narrow_iban_mask(), name, "narrow_mask");
+            pipeline.extract_iban_in_image(&iban_image, name, Region::NarrowIbanMask, cleaned)
+        })?;
+
+    let titulaire = titulaire();
+    let provenance = provenance(iban_provenance, full_page, titulaire.as_ref());
+    Rib::from_candidates(
+        candidates,
+        titulaire.map(|(t, _)| t),
+        &layout_text,
+        provenance,
+    )
+}
+
+// the page straightened with the hocr angle, then the iban mask of the hocr anchor
+pub(crate) fn tesseract_extract_page(
+    pipeline: &Pipeline,
+    img: &DynamicImage,
+    name: &str,
+    cleaned: bool,
+) -> Option<Rib> {
+    let (layout, maybe_angle, maybe_anchor) = tess_analyze(img)
+        .inspect_err(|e| log::warn!("{}", e))
+        .ok()?;
+
+    let (img, layout, maybe_anchor) = match maybe_angle {
+        Some(angle) => {
+            let rotated_img = rotate(img, angle);
+            let (new_layout, _, new_anchor) = tess_analyze(&rotated_img)
+                .inspect_err(|e| log::warn!("{}", e))
+                .ok()?;
+            (rotated_img, new_layout, new_anchor)
+        }
+        None => (img.clone(), layout, maybe_anchor),
+    };
+
+    let layout_text = layout.to_layout_text();
+    let full_page = FieldProvenance::new(Source::Tesseract, Some(Region::FullPage), cleaned);
+    if Statement::detect(&layout_text) {
+        trace!("statement read on the page of {}", name);
+        return Rib::parse_statement(layout_text, IbanSource::Tesseract, full_page);
+    }
 
-    let titulaire = titulaires
-        .first()
-        .map(|s| s.lines().map(|l| l.to_string()).collect());
+    let anchor = maybe_anchor?;
+    trace!("tess anchor found");
 
-    if titulaire.is_some() {
-        return titulaire;
+    let iban_image = crop(&img, anchor.iban_mask(), name, "mask");
+
+    let iban_image = only_rotate(&iban_image, name);
+    let iban_image = resize(&iban_image, anchor.height, OPTIMAL_TESSERACT_HEIGHT);
+    save_image_in_debug(&iban_image, name, "rotated_resized_mask");
+
+    let (candidates, iban_provenance) =
+        pipeline.extract_iban_in_image(&iban_image, name, Region::RotatedIbanMask, cleaned)?;
+
+    let titulaire = titulaire_in_layout(&layout_text, Source::Tesseract, cleaned)
+        .or_else(|| pipeline.zoom_and_extract_titulaire(&img, &layout, name, cleaned));
+    let provenance = provenance(iban_provenance, full_page, titulaire.as_ref());
+
+    Rib::from_candidates(
+        candidates,
+        titulaire.map(|(t, _)| t),
+        &layout_text,
+        provenance,
+    )
+}
+
+// the bank name is set by Rib::from_candidates
+pub(crate) fn provenance(
+    iban: FieldProvenance,
+    bic: FieldProvenance,
+    titulaire: Option<&(Vec<String>, FieldProvenance)>,
+) -> Provenance {
+    Provenance {
+        iban: Some(iban),
+        bic: Some(bic),
+        titulaire: titulaire.map(|(_, provenance)| provenance.clone()),
+        bank_name: None,
     }
+}
 
-    let titulaire_word_regex = Regex::new(r"(?i)titulaire").unwrap();
-    let (_, _text_lines, titulaire_anchors) = ocrs_anchors(img, &titulaire_word_regex, None);
+// the heuristics of the text layer, on the ocr put back in its columns
+fn titulaire_in_layout(
+    layout_text: &str,
+    source: Source,
+    cleaned: bool,
+) -> Option<(Vec<String>, FieldProvenance)> {
+    find_titulaire_addr(layout_text)
+        .map(|addr| addr.lines())
+        .filter(|lines| !lines.is_empty())
+        .or_else(|| find_simple_titulaire(layout_text, 3))
+        .map(|titulaire| {
+            (
+                titulaire,
+                FieldProvenance::new(source, Some(Region::FullPage), cleaned),
+            )
+        })
+}
 
-    titulaire_anchors
+// the boxes of the words matching word_regex, on the lines matching line_regex
+fn anchors(layout: &Layout, word_regex: &Regex, line_regex: Option<&Regex>) -> Vec<Anchor> {
+    layout
+        .pages
         .iter()
-        .enumerate()
-        .map(|(index, anchor)| {
-            let cropped_img = crop(
-                img,
-                anchor.titulaire_mask(),
-                name,
-                &format!(r#"{}_titulaire_mask"#, index),
-            );
-            image_to_string_using_ocrs(cropped_img)
+        .flat_map(|page| page.lines.iter())
+        .filter(|line| line_regex.is_none_or(|regex| regex.is_match(&line.text())))
+        .flat_map(|line| line.words.iter())
+        .filter(|word| word_regex.is_match(&word.text))
+        .map(|word| {
+            let bbox = word.bbox;
+            Anchor::new(
+                Point::new(bbox.x0.round() as u32, bbox.y0.round() as u32),
+                Point::new(bbox.x1.round() as u32, bbox.y1.round() as u32),
+            )
         })
-        .filter(|text| titulaire_word_regex.is_match(text))
-        .find_map(|text| find_simple_titulaire(&text, 1))
+        .collect()
 }
 
 fn crop(
@@ -233,23 +468,147 @@ fn bytes_to_img(content: Vec<u8>) -> Option<DynamicImage> {
     Some(img.into_luma8().into())
 }
 
-fn extract_iban_in_image(cropped_img: &DynamicImage, name: &str) -> Option<String> {
-    let tess_iban = img_to_string_using_tesseract(cropped_img.clone());
-    if let Some(iban) = extract_iban(&tess_iban) {
-        return Some(iban);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        layout::{BBox, Line, Page, Word},
+        ocr_backend::Fake,
     };
+    use image::ImageFormat;
+
+    fn blank_png() -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        DynamicImage::new_luma8(400, 200)
+            .write_to(&mut buffer, ImageFormat::Png)
+            .unwrap();
+        buffer.into_inner()
+    }
 
-    let ocrs_iban = image_to_string_using_ocrs(cropped_img.clone());
-    if let Some(iban) = extract_iban(&ocrs_iban) {
-        return Some(iban);
-    };
+    #[test]
+    fn test_pipeline_with_fake_backend() {
+        let text = std::fs::read_to_string("tests/fixtures/rib/lcl.txt").unwrap();
+        let pipeline = Pipeline::new(vec![Box::new(Fake::new(&text))], vec![]);
+
+        let rib = pipeline.image_bytes_to_rib(blank_png(), "fake").unwrap();
+        let rib = serde_json::to_value(rib).unwrap();
+
+        assert_eq!(rib["iban"], "FR76 3000 1000 6449 1900 9562 088");
+        assert_eq!(rib["iban_candidates"][0]["source"], "ocrs");
+        assert_eq!(rib["provenance"]["iban"]["region"], "full_page");
+    }
 
-    log::trace!(
-        "not found for {}: tess_iban: {}, ocrs_iban: {}",
-        name,
-        tess_iban,
-        ocrs_iban
-    );
+    #[test]
+    fn test_pipeline_statement() {
+        let text = std::fs::read_to_string("tests/fixtures/rib/releve_de_compte.txt").unwrap();
+        let pipeline = Pipeline::new(vec![Box::new(Fake::new(&text))], vec![]);
 
-    None
+        let rib = pipeline.image_bytes_to_rib(blank_png(), "fake").unwrap();
+        assert!(rib.is_statement());
+        let rib = serde_json::to_value(rib).unwrap();
+
+        // the ibans of the transactions are ignored
+        assert_eq!(rib["iban"], "FR27 1820 6000 3060 0018 2530 127");
+        assert_eq!(rib["provenance"]["iban"]["source"], "ocrs");
+        assert_eq!(rib["provenance"]["titulaire"]["source"], "ocrs");
+    }
+
+    #[test]
+    fn test_pipeline_iban_mask() {
+        // the iban of the page is unreadable, its mask is read again by the engines of the crops
+        let page = "RELEVE D'IDENTITE BANCAIRE
+Titulaire du compte : M MATISSE HENRI
+IBAN FR76 3000 1000 64";
+        let crop = Fake {
+            text: "FR76 3000 1000 6449 1900 9562 088".to_string(),
+            source: IbanSource::Tesseract,
+        };
+        let pipeline = Pipeline::new(vec![Box::new(Fake::new(page))], vec![Box::new(crop)]);
+
+        let rib = pipeline.image_bytes_to_rib(blank_png(), "fake").unwrap();
+        let rib = serde_json::to_value(rib).unwrap();
+
+        assert_eq!(rib["iban"], "FR76 3000 1000 6449 1900 9562 088");
+        assert_eq!(rib["provenance"]["iban"]["source"], "tesseract");
+        assert_eq!(rib["provenance"]["iban"]["region"], "iban_mask");
+        assert_eq!(rib["iban_candidates"][0]["source"], "tesseract");
+
+        // without engine for the crops, nothing reads the mask
+        let pipeline = Pipeline::new(vec![Box::new(Fake::new(page))], vec![]);
+        assert!(pipeline.image_bytes_to_rib(blank_png(), "fake").is_none());
+    }
+
+    #[test]
+    fn test_pipeline_titulaire_mask() {
+        // the name next to the label is unreadable, the block of the label is read again
+        let page = "Titulaire du compte
+Domiciliation : LCL PARIS
+IBAN FR76 3000 1000 6449 1900 9562 088";
+        let crop = Fake {
+            text: "Titulaire du compte : M MATISSE HENRI".to_string(),
+            source: IbanSource::Tesseract,
+        };
+        let pipeline = Pipeline::new(vec![Box::new(Fake::new(page))], vec![Box::new(crop)]);
+
+        let rib = pipeline.image_bytes_to_rib(blank_png(), "fake").unwrap();
+        let rib = serde_json::to_value(rib).unwrap();
+
+        assert_eq!(rib["titulaire"], serde_json::json!(["M MATISSE HENRI"]));
+        assert_eq!(rib["provenance"]["titulaire"]["source"], "tesseract");
+        assert_eq!(rib["provenance"]["titulaire"]["region"], "titulaire_mask");
+    }
+
+    // a line read by the engine from x, each character 18 pixels wide
+    fn line(x: f32, y: f32, text: &str) -> Line {
+        let mut x = x;
+        let words = text
+            .split(' ')
+            .map(|word| {
+                let width = word.chars().count() as f32 * 18.0;
+                let bbox = BBox::new(x, y, x + width, y + 30.0);
+                x += width + 18.0;
+                Word {
+                    text: word.to_string(),
+                    bbox,
+                    confidence: None,
+                }
+            })
+            .collect();
+        Line { words }
+    }
+
+    #[test]
+    fn test_titulaire_in_layout() {
+        // two blocks side by side, the engine reads each one line by line
+        let lines = vec![
+            line(100.0, 100.0, "DOMICILIATION"),
+            line(100.0, 150.0, "CL PARIS JOURDAIN"),
+            line(100.0, 200.0, "12 RUE DE MEAUX"),
+            line(100.0, 250.0, "75019 PARIS"),
+            line(1000.0, 100.0, "TITULAIRE"),
+            line(1000.0, 150.0, "M MATISSE HENRI"),
+            line(1000.0, 200.0, "18 RUE CARNOT"),
+            line(1000.0, 250.0, "92120 MONTROUGE"),
+        ];
+        let layout = Layout {
+            pages: vec![Page::new(1654.0, 2339.0, lines)],
+        };
+
+        let (titulaire, provenance) =
+            titulaire_in_layout(&layout.to_layout_text(), Source::Tesseract, false).unwrap();
+        assert_eq!(
+            titulaire,
+            vec!["M MATISSE HENRI", "18 RUE CARNOT", "92120 MONTROUGE"]
+        );
+        assert_eq!(provenance.source, Source::Tesseract);
+    }
+
+    #[test]
+    fn test_pipeline_without_backend() {
+        let pipeline = Pipeline::new(vec![Box::new(Fake::new("no iban here"))], vec![]);
+        assert!(pipeline.image_bytes_to_rib(blank_png(), "fake").is_none());
+
+        let pipeline = Pipeline::new(vec![], vec![]);
+        assert!(pipeline.image_bytes_to_rib(blank_png(), "fake").is_none());
+    }
 }
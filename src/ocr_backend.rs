@@ -0,0 +1,277 @@
+use image::DynamicImage;
+
+use crate::{
+    layout::{BBox, Layout, Line, Page, Word},
+    ocr::{extract_page, tesseract_extract_page, Pipeline},
+    ocrs::{image_to_layout, image_to_string_using_ocrs},
+    rib::{IbanSource, Rib},
+    tesseract::img_to_string_and_confidence_using_tesseract,
+    tools::tools,
+};
+
+// the text read in an image, with the mean confidence of the engine between 0 and 1
+// when it gives one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recognition {
+    pub text: String,
+    pub confidence: Option<f64>,
+}
+
+pub trait OcrBackend: Send + Sync {
+    // the name used in LA_TAUPE_OCR_PAGE_BACKENDS and LA_TAUPE_OCR_CROP_BACKENDS
+    fn name(&self) -> &'static str;
+
+    fn source(&self) -> IbanSource;
+
+    // false when its program or its models are missing, it is then left out
+    fn available(&self) -> bool {
+        true
+    }
+
+    fn recognize(&self, img: &DynamicImage) -> Recognition;
+
+    // the words of the page with their boxes, None when the engine only gives the text
+    fn layout(&self, _img: &DynamicImage) -> Option<Layout> {
+        None
+    }
+
+    // the strategy on the full page: the ibans of the whole text, then the iban masks
+    // around the anchors of the layout, read by the engines of the crops
+    fn extract_page(
+        &self,
+        pipeline: &Pipeline,
+        img: &DynamicImage,
+        name: &str,
+        cleaned: bool,
+    ) -> Option<Rib> {
+        let layout = self.layout(img);
+        let text = match &layout {
+            Some(layout) => layout.text(),
+            None => self.recognize(img).text,
+        };
+
+        extract_page(
+            pipeline,
+            img,
+            (&text, layout.as_ref()),
+            self.source(),
+            name,
+            cleaned,
+        )
+    }
+}
+
+pub struct Ocrs;
+
+impl OcrBackend for Ocrs {
+    fn name(&self) -> &'static str {
+        "ocrs"
+    }
+
+    fn source(&self) -> IbanSource {
+        IbanSource::Ocrs
+    }
+
+    fn available(&self) -> bool {
+        crate::ocrs::is_available()
+    }
+
+    fn recognize(&self, img: &DynamicImage) -> Recognition {
+        Recognition {
+            text: image_to_string_using_ocrs(img.clone()),
+            confidence: None,
+        }
+    }
+
+    fn layout(&self, img: &DynamicImage) -> Option<Layout> {
+        Some(image_to_layout(img))
+    }
+}
+
+pub struct Tesseract;
+
+impl OcrBackend for Tesseract {
+    fn name(&self) -> &'static str {
+        "tesseract"
+    }
+
+    fn source(&self) -> IbanSource {
+        IbanSource::Tesseract
+    }
+
+    fn available(&self) -> bool {
+        tools().tesseract.is_some()
+    }
+
+    fn recognize(&self, img: &DynamicImage) -> Recognition {
+        let (text, confidence) = img_to_string_and_confidence_using_tesseract(img.clone())
+            .unwrap_or_else(|e| {
+                log::warn!("{}", e);
+                (String::new(), None)
+            });
+        Recognition { text, confidence }
+    }
+
+    // the page straightened with the hocr angle, then the iban mask of the hocr anchor
+    fn extract_page(
+        &self,
+        pipeline: &Pipeline,
+        img: &DynamicImage,
+        name: &str,
+        cleaned: bool,
+    ) -> Option<Rib> {
+        tesseract_extract_page(pipeline, img, name, cleaned)
+    }
+}
+
+// always reads the same text, to test the pipeline without the models nor tesseract.
+// its layout puts each character in a cell of CELL_WIDTH by CELL_HEIGHT pixels
+pub struct Fake {
+    pub text: String,
+    pub source: IbanSource,
+}
+
+impl Fake {
+    pub fn new(text: &str) -> Self {
+        Fake {
+            text: text.to_string(),
+            source: IbanSource::Ocrs,
+        }
+    }
+}
+
+impl OcrBackend for Fake {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn source(&self) -> IbanSource {
+        self.source
+    }
+
+    fn recognize(&self, _img: &DynamicImage) -> Recognition {
+        Recognition {
+            text: self.text.clone(),
+            confidence: None,
+        }
+    }
+
+    fn layout(&self, _img: &DynamicImage) -> Option<Layout> {
+        Some(monospaced_layout(&self.text))
+    }
+}
+
+const CELL_WIDTH: f32 = 10.0;
+const CELL_HEIGHT: f32 = 20.0;
+
+// the words of the text where a monospaced font would print them
+fn monospaced_layout(text: &str) -> Layout {
+    let lines = text
+        .lines()
+        .enumerate()
+        .map(|(row, line)| {
+            let mut words: Vec<Word> = vec![];
+            let mut previous_is_space = true;
+
+            for (col, c) in line.chars().enumerate() {
+                let (x, y) = (col as f32 * CELL_WIDTH, row as f32 * CELL_HEIGHT);
+                match words.last_mut() {
+                    Some(word) if !c.is_whitespace() && !previous_is_space => {
+                        word.text.push(c);
+                        word.bbox.x1 = x + CELL_WIDTH;
+                    }
+                    _ if !c.is_whitespace() => words.push(Word {
+                        text: c.to_string(),
+                        bbox: BBox::new(x, y, x + CELL_WIDTH, y + CELL_HEIGHT),
+                        confidence: None,
+                    }),
+                    _ => {}
+                }
+                previous_is_space = c.is_whitespace();
+            }
+
+            Line { words }
+        })
+        .filter(|line| !line.words.is_empty())
+        .collect::<Vec<Line>>();
+
+    let width = text.lines().map(|line| line.chars().count()).max();
+    Layout {
+        pages: vec![Page {
+            width: width.unwrap_or_default() as f32 * CELL_WIDTH,
+            height: text.lines().count() as f32 * CELL_HEIGHT,
+            lines,
+        }],
+    }
+}
+
+// ex: "tesseract,ocrs", a backend left out is disabled
+pub fn parse_backends(list: &str) -> Vec<Box<dyn OcrBackend>> {
+    list.split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| -> Option<Box<dyn OcrBackend>> {
+            match name.as_str() {
+                "ocrs" => Some(Box::new(Ocrs)),
+                "tesseract" => Some(Box::new(Tesseract)),
+                _ => {
+                    log::warn!("unknown ocr backend: {}", name);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+pub fn backends_from_env(var: &str, default: &str) -> Vec<Box<dyn OcrBackend>> {
+    let list = std::env::var(var)
+        .ok()
+        .filter(|list| !list.trim().is_empty())
+        .unwrap_or_else(|| default.to_string());
+
+    parse_backends(&list)
+        .into_iter()
+        .filter(|backend| {
+            let available = backend.available();
+            if !available {
+                log::warn!("{} is not available, left out of {}", backend.name(), var);
+            }
+            available
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_backends() {
+        let names = |list: &str| {
+            parse_backends(list)
+                .iter()
+                .map(|backend| backend.name())
+                .collect::<Vec<&str>>()
+        };
+
+        assert_eq!(names("ocrs,tesseract"), vec!["ocrs", "tesseract"]);
+        assert_eq!(names(" Tesseract , ocrs "), vec!["tesseract", "ocrs"]);
+        assert_eq!(names("ocrs,unknown"), vec!["ocrs"]);
+        assert!(names("").is_empty());
+    }
+
+    #[test]
+    fn test_fake() {
+        let img = DynamicImage::new_luma8(10, 10);
+        let fake = Fake::new("IBAN FR76 3000 1000 6449 1900 9562 088");
+
+        assert_eq!(fake.recognize(&img).text, fake.text);
+        assert_eq!(fake.source(), IbanSource::Ocrs);
+
+        let layout = fake.layout(&img).unwrap();
+        assert_eq!(layout.text(), "IBAN FR76 3000 1000 6449 1900 9562 088");
+        let fr76 = &layout.pages[0].lines[0].words[1];
+        assert_eq!(fr76.text, "FR76");
+        assert_eq!(fr76.bbox, BBox::new(50.0, 0.0, 90.0, 20.0));
+    }
+}
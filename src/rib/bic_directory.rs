@@ -0,0 +1,175 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+// bic, french bank codes and institution, separated by tabs
+const BIC_DIRECTORY_TSV: &str = include_str!("bic_directory.tsv");
+
+// the bundled directory, or the file set in LA_TAUPE_BIC_DIRECTORY
+pub static BIC_DIRECTORY: LazyLock<BicDirectory> = LazyLock::new(|| {
+    std::env::var("LA_TAUPE_BIC_DIRECTORY")
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+        .and_then(|path| match BicDirectory::from_file(&path) {
+            Ok(directory) => Some(directory),
+            Err(e) => {
+                log::warn!("{}, using the bundled bic directory", e);
+                None
+            }
+        })
+        .unwrap_or_else(|| BicDirectory::parse(BIC_DIRECTORY_TSV))
+});
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Institution {
+    pub bic: String,
+    pub bank_codes: Vec<String>,
+    pub name: String,
+}
+
+pub struct BicDirectory {
+    institutions: Vec<Institution>,
+    // bic (8 characters) to the index of the institution
+    by_bic: HashMap<String, usize>,
+    // french bank code to the index of the institution
+    by_bank_code: HashMap<String, usize>,
+}
+
+impl BicDirectory {
+    pub fn parse(content: &str) -> Self {
+        let institutions = content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let fields = line.split('\t').collect::<Vec<&str>>();
+                if fields.len() < 3 {
+                    return None;
+                }
+
+                Some(Institution {
+                    bic: bic8(fields[0]),
+                    bank_codes: fields[1]
+                        .split(',')
+                        .map(|code| code.trim().to_string())
+                        .filter(|code| !code.is_empty())
+                        .collect(),
+                    name: fields[2].trim().to_string(),
+                })
+            })
+            .collect::<Vec<Institution>>();
+
+        let mut by_bic = HashMap::new();
+        let mut by_bank_code = HashMap::new();
+
+        for (index, institution) in institutions.iter().enumerate() {
+            by_bic.insert(institution.bic.clone(), index);
+            for code in &institution.bank_codes {
+                by_bank_code.insert(code.clone(), index);
+            }
+        }
+
+        BicDirectory {
+            institutions,
+            by_bic,
+            by_bank_code,
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        std::fs::read_to_string(path)
+            .map(|content| BicDirectory::parse(&content))
+            .map_err(|e| format!("unable to read the bic directory {}: {}", path, e))
+    }
+
+    // the branch code, the last 3 characters, is ignored
+    pub fn by_bic(&self, bic: &str) -> Option<&Institution> {
+        self.by_bic
+            .get(&bic8(bic))
+            .map(|index| &self.institutions[*index])
+    }
+
+    pub fn by_iban(&self, iban: &str) -> Option<&Institution> {
+        self.by_bank_code
+            .get(&french_bank_code(iban)?)
+            .map(|index| &self.institutions[*index])
+    }
+
+    // None when the bic is not in the directory, or for a foreign iban
+    pub fn bic_matches_iban(&self, bic: &str, iban: &str) -> Option<bool> {
+        let bank_code = french_bank_code(iban)?;
+        let institution = self.by_bic(bic)?;
+
+        Some(institution.bank_codes.contains(&bank_code))
+    }
+}
+
+fn bic8(bic: &str) -> String {
+    bic.chars()
+        .filter(|c| !c.is_whitespace())
+        .take(8)
+        .collect::<String>()
+        .to_uppercase()
+}
+
+// the 5 digits following the check digits, only for french and monegasque ibans
+fn french_bank_code(iban: &str) -> Option<String> {
+    let iban = iban.replace(" ", "");
+    if !iban.starts_with("FR") && !iban.starts_with("MC") {
+        return None;
+    }
+    iban.get(4..9).map(|code| code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_by_bic() {
+        let institution = BIC_DIRECTORY.by_bic("AGRI FR PP 847").unwrap();
+        assert_eq!(institution.name, "Crédit Agricole");
+        assert!(institution.bank_codes.contains(&"14706".to_string()));
+
+        assert_eq!(BIC_DIRECTORY.by_bic("DEUTDEFF"), None);
+    }
+
+    #[test]
+    fn test_bic_matches_iban() {
+        let iban = "FR76 3000 1000 6449 1900 9562 088";
+        assert_eq!(
+            BIC_DIRECTORY.bic_matches_iban("BDFEFRPPCCT", iban),
+            Some(true)
+        );
+        assert_eq!(
+            BIC_DIRECTORY.bic_matches_iban("AGRIFRPP847", iban),
+            Some(false)
+        );
+        // an unknown bic may be a new one of the bank
+        assert_eq!(BIC_DIRECTORY.bic_matches_iban("UNKNFRPP", iban), None);
+
+        let iban = "FR76 9999 9000 0000 0000 0000 000";
+        assert_eq!(BIC_DIRECTORY.bic_matches_iban("UNKNFRPP", iban), None);
+
+        let iban = "DE89 3704 0044 0532 0130 00";
+        assert_eq!(BIC_DIRECTORY.bic_matches_iban("COBADEFF", iban), None);
+    }
+
+    #[test]
+    fn test_by_iban() {
+        let institution = BIC_DIRECTORY.by_iban("FR2718206000306000182530127");
+        assert_eq!(institution.map(|i| i.bic.as_str()), Some("AGRIFRPP"));
+    }
+
+    #[test]
+    fn test_from_file() {
+        assert!(BicDirectory::from_file("does/not/exist.tsv").is_err());
+
+        let directory = BicDirectory::parse("TESTFRP0\t99999\tBanque de test\n");
+        assert_eq!(
+            directory.by_iban("FR76 9999 9000 0000 0000 0000 000"),
+            Some(&Institution {
+                bic: "TESTFRP0".to_string(),
+                bank_codes: vec!["99999".to_string()],
+                name: "Banque de test".to_string(),
+            })
+        );
+    }
+}
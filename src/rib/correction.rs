@@ -0,0 +1,235 @@
+use iban::Iban;
+use itertools::Itertools;
+
+use crate::rib::fr::RibFr;
+
+// characters commonly mixed up by the ocr
+const CONFUSIONS: &[&[char]] = &[
+    &['O', '0', 'D', 'Q'],
+    &['I', '1', 'l', 'L'],
+    &['S', '5'],
+    &['B', '8'],
+    &['Z', '2'],
+    &['G', '6'],
+];
+
+// beyond, the result is more likely a random valid iban than the printed one
+pub const MAX_SUBSTITUTIONS: usize = 3;
+// bound of the number of tried ibans, beyond nothing is corrected
+// rather than an answer depending on the search order
+const MAX_TRIES: usize = 20_000;
+// the bban of these countries is a french rib, with a clé RIB
+const RIB_COUNTRIES: &[&str] = &["FR", "MC"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Correction {
+    pub iban: Iban,
+    pub substitutions: usize,
+}
+
+// the valid ibans obtained with the fewest substitutions of confusable characters.
+// several ibans are returned when there are several solutions with the same
+// number of substitutions. only for an ocr reading, a text layer is exact
+pub fn correct_iban(raw: &str) -> Vec<Correction> {
+    let chars: Vec<char> = raw
+        .chars()
+        .map(|c| if c == 'l' { c } else { c.to_ascii_uppercase() })
+        .collect();
+    let country = chars.iter().take(2).collect::<String>();
+    let rib_country = RIB_COUNTRIES.contains(&country.as_str());
+
+    // the check digits can't be letters, they have to be substituted
+    let mandatory: Vec<usize> = (2..4.min(chars.len()))
+        .filter(|i| !chars[*i].is_ascii_digit())
+        .collect();
+
+    let positions: Vec<(usize, Vec<char>)> = chars
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| alternatives(i, *c, rib_country).map(|alternatives| (i, alternatives)))
+        .collect();
+
+    if mandatory
+        .iter()
+        .any(|i| !positions.iter().any(|(position, _)| position == i))
+    {
+        return vec![];
+    }
+
+    if mandatory.is_empty() {
+        if let Ok(iban) = chars
+            .iter()
+            .collect::<String>()
+            .to_ascii_uppercase()
+            .parse::<Iban>()
+        {
+            return vec![Correction {
+                iban,
+                substitutions: 0,
+            }];
+        }
+    }
+
+    let mut tries = 0;
+
+    for nb_substitutions in 1..=MAX_SUBSTITUTIONS.min(positions.len()) {
+        let mut found = vec![];
+
+        for substituted in (0..positions.len()).combinations(nb_substitutions) {
+            let substituted_positions: Vec<usize> =
+                substituted.iter().map(|j| positions[*j].0).collect();
+
+            if mandatory.iter().any(|i| !substituted_positions.contains(i)) {
+                continue;
+            }
+
+            let choices = substituted
+                .iter()
+                .map(|j| positions[*j].1.clone())
+                .multi_cartesian_product();
+
+            for choice in choices {
+                tries += 1;
+                if tries > MAX_TRIES {
+                    log::trace!("too many tries to correct {}", raw);
+                    return vec![];
+                }
+
+                let mut candidate = chars.clone();
+                for (position, c) in substituted_positions.iter().zip(choice) {
+                    candidate[*position] = c;
+                }
+
+                let candidate: String = candidate.into_iter().collect();
+                if let Ok(iban) = candidate.to_ascii_uppercase().parse::<Iban>() {
+                    if rib_country && !is_valid_cle_rib(&iban) {
+                        continue;
+                    }
+
+                    found.push(Correction {
+                        iban,
+                        substitutions: nb_substitutions,
+                    });
+                }
+            }
+        }
+
+        if !found.is_empty() {
+            return found;
+        }
+    }
+
+    vec![]
+}
+
+// the iban as printed, for a text layer which has no ocr confusion
+pub fn exact_iban(raw: &str) -> Vec<Correction> {
+    raw.to_ascii_uppercase()
+        .parse::<Iban>()
+        .map(|iban| {
+            vec![Correction {
+                iban,
+                substitutions: 0,
+            }]
+        })
+        .unwrap_or_default()
+}
+
+// the bban of a french rib: banque, guichet, compte and clé
+fn is_valid_cle_rib(iban: &Iban) -> bool {
+    let bban = iban.to_string().replace(' ', "")[4..].to_string();
+    if bban.len() != 23 {
+        return false;
+    }
+
+    RibFr::new(&bban[..5], &bban[5..10], &bban[10..21], &bban[21..]).cle_rib_valid
+}
+
+// the characters which could have been read instead of c.
+// the country code is made of letters and the check digits of digits.
+// a digit of a french rib is never turned into a letter, its account
+// number may contain letters but they are rare
+fn alternatives(position: usize, c: char, rib_country: bool) -> Option<Vec<char>> {
+    let group = CONFUSIONS.iter().find(|group| group.contains(&c))?;
+
+    if rib_country && position > 3 && c.is_ascii_digit() {
+        return None;
+    }
+
+    let alternatives: Vec<char> = group
+        .iter()
+        .filter(|other| **other != c && **other != 'l')
+        .filter(|other| match position {
+            0 | 1 => other.is_ascii_uppercase(),
+            2 | 3 => other.is_ascii_digit(),
+            _ if rib_country => other.is_ascii_digit(),
+            _ => true,
+        })
+        .copied()
+        .collect();
+
+    if alternatives.is_empty() {
+        None
+    } else {
+        Some(alternatives)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ibans(corrections: Vec<Correction>) -> Vec<(String, usize)> {
+        corrections
+            .into_iter()
+            .map(|c| (c.iban.to_string(), c.substitutions))
+            .collect()
+    }
+
+    #[test]
+    fn test_correct_iban() {
+        let iban = "FR76 3000 1000 6449 1900 9562 088".to_string();
+
+        assert_eq!(
+            ibans(correct_iban("FR7630001000644919009562088")),
+            vec![(iban.clone(), 0)]
+        );
+
+        assert_eq!(ibans(correct_iban("FR763OOO1OOO644919OO9562O88")), vec![]);
+
+        assert_eq!(
+            ibans(correct_iban("FR7G3000100064491900956Z088")),
+            vec![(iban.clone(), 2)]
+        );
+
+        assert_eq!(
+            ibans(correct_iban("FR763000lOO0644919009562088")),
+            vec![(iban.clone(), 3)]
+        );
+    }
+
+    #[test]
+    fn test_correct_iban_rib_country() {
+        // the digits of a french bban are never turned into letters,
+        // FR76 1820 6000 306O O018 2530 127 would have a valid checksum
+        assert_eq!(ibans(correct_iban("FR7618206000306000182530127")), vec![]);
+
+        // a letter turned into a digit must give a valid clé RIB
+        assert_eq!(
+            ibans(correct_iban("FR76300010006449190O9562088")),
+            vec![("FR76 3000 1000 6449 1900 9562 088".to_string(), 1)]
+        );
+
+        // FR60 3000 1000 6449 1900 9562 085 has a valid checksum but a wrong clé RIB
+        assert_eq!(ibans(correct_iban("FR6O30001000644919009562085")), vec![]);
+    }
+
+    #[test]
+    fn test_correct_iban_check_digits() {
+        // the check digits are necessarily substituted
+        assert_eq!(
+            ibans(correct_iban("DEB9370400440532013000")),
+            vec![("DE89 3704 0044 0532 0130 00".to_string(), 1)]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::text::{
+    address::{find_domiciliation_addr, PostalAddress},
+    fuzzy::best_match,
+};
+
+const BANK_NAME_THRESHOLD: f32 = 0.8;
+
+// the branch of the bank holding the account
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Domiciliation {
+    pub branch_name: Option<String>,
+    pub address: Vec<String>,
+    pub postal_code: Option<String>,
+    pub city: Option<String>,
+}
+
+impl Domiciliation {
+    fn from_lines(lines: &[String]) -> Option<Self> {
+        let header =
+            Regex::new(r"(?i)^(agence de )?domiciliation( bancaire)?( / paying bank)?\s*:?")
+                .unwrap();
+        let street_number = Regex::new(r"^\d").unwrap();
+
+        let lines = lines
+            .iter()
+            .map(|line| header.replace(line.trim(), "").trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<String>>();
+
+        let mut postal_address = PostalAddress::parse(&lines);
+
+        // the block has to end with the postal code and the city
+        postal_address.postal_code.as_ref()?;
+
+        // the name of the branch precedes the street
+        let branch_name = match postal_address.lines.first() {
+            Some(line) if !street_number.is_match(line) => Some(postal_address.lines.remove(0)),
+            _ => None,
+        };
+
+        Some(Domiciliation {
+            branch_name,
+            address: postal_address.lines,
+            postal_code: postal_address.postal_code,
+            city: postal_address.city,
+        })
+    }
+
+    // the bank name from the iban is found in the domiciliation
+    pub fn matches_bank_name(&self, bank_name: &str) -> bool {
+        let text = self
+            .branch_name
+            .iter()
+            .chain(self.address.iter())
+            .cloned()
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        best_match(bank_name, &text).is_some_and(|(_, score)| score >= BANK_NAME_THRESHOLD)
+    }
+}
+
+pub fn find_domiciliation(text: &str) -> Option<Domiciliation> {
+    if let Some(addr) = find_domiciliation_addr(text) {
+        if let Some(domiciliation) = Domiciliation::from_lines(&addr.inner_lines) {
+            return Some(domiciliation);
+        }
+    }
+
+    // without address, only the name of the branch is printed:
+    // after the label on the same line or on the next one
+    let same_line = Regex::new(r"(?i)domiciliation\s*:\s*(.+)$").unwrap();
+    let label = Regex::new(r"(?i)^\s*domiciliation\s*$").unwrap();
+
+    let lines = text.lines().collect::<Vec<&str>>();
+
+    lines.iter().enumerate().find_map(|(index, line)| {
+        let branch_name = if let Some(caps) = same_line.captures(line) {
+            caps[1].trim().to_string()
+        } else if label.is_match(line) {
+            lines.get(index + 1)?.trim().to_string()
+        } else {
+            return None;
+        };
+
+        // a single letter is an ocr or layout artifact
+        if branch_name.chars().filter(|c| c.is_alphanumeric()).count() < 2 {
+            return None;
+        }
+
+        Some(Domiciliation {
+            branch_name: Some(branch_name),
+            address: vec![],
+            postal_code: None,
+            city: None,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_in_file(path: &str) -> Option<Domiciliation> {
+        let text = std::fs::read_to_string(path).unwrap();
+        find_domiciliation(&text)
+    }
+
+    #[test]
+    fn domiciliation_with_address() {
+        let domiciliation = find_in_file("tests/fixtures/rib/sg.txt").unwrap();
+        assert_eq!(
+            domiciliation,
+            Domiciliation {
+                branch_name: Some("TOURS HEURTELOUP".to_string()),
+                address: vec!["3 bd heurteloup".to_string()],
+                postal_code: Some("37000".to_string()),
+                city: Some("Tours".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn domiciliation_without_address() {
+        let domiciliation = find_in_file("tests/fixtures/rib/lcl.txt").unwrap();
+        assert_eq!(
+            domiciliation.branch_name,
+            Some("CL PARIS JOURDAIN (00457)".to_string())
+        );
+
+        let domiciliation = find_in_file("tests/fixtures/rib/credit_agricole_2.txt").unwrap();
+        assert_eq!(
+            domiciliation.branch_name,
+            Some("NANTES CARRE LAFAYETTE".to_string())
+        );
+        assert!(!domiciliation.matches_bank_name("BoursoBank"));
+    }
+}
@@ -0,0 +1,242 @@
+use iban::Iban;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// the table of a french RIB: code banque, code guichet, numéro de compte et clé RIB
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RibFr {
+    pub code_banque: String,
+    pub code_guichet: String,
+    pub numero_compte: String,
+    pub cle_rib: String,
+    pub cle_rib_valid: bool,
+}
+
+impl RibFr {
+    pub fn new(code_banque: &str, code_guichet: &str, numero_compte: &str, cle_rib: &str) -> Self {
+        let mut rib_fr = RibFr {
+            code_banque: code_banque.to_string(),
+            code_guichet: code_guichet.to_string(),
+            numero_compte: numero_compte.to_uppercase(),
+            cle_rib: cle_rib.to_string(),
+            cle_rib_valid: false,
+        };
+
+        rib_fr.cle_rib_valid = rib_fr.expected_cle_rib() == rib_fr.cle_rib;
+        rib_fr
+    }
+
+    // the components are printed in a row under their labels,
+    // or each one after its label
+    pub fn find(text: &str) -> Option<Self> {
+        // a row is on a single line, numbers on following lines are not one
+        let row = Regex::new(
+            r"(?m)(?:^|\s)(\d{5})[ \t]+(\d{5})[ \t]+([[:alnum:]]{11})[ \t]+(\d{2})(?:\s|$)",
+        )
+        .unwrap();
+
+        // the first row with a valid clé, a stray row may come before
+        let rows = row
+            .captures_iter(text)
+            .map(|caps| RibFr::new(&caps[1], &caps[2], &caps[3], &caps[4]))
+            .collect::<Vec<RibFr>>();
+
+        if let Some(rib_fr) = rows
+            .iter()
+            .find(|rib_fr| rib_fr.cle_rib_valid)
+            .or(rows.first())
+        {
+            return Some(rib_fr.clone());
+        }
+
+        let labelled = |label: &str, value: &str| {
+            Regex::new(&format!(r"(?i){}\s*:?\s*({})", label, value))
+                .unwrap()
+                .captures(text)
+                .map(|caps| caps[1].to_string())
+        };
+
+        let code_banque = labelled(r"code\s+banque", r"\d{5}")?;
+        let code_guichet = labelled(r"code\s+guichet", r"\d{5}")?;
+        let numero_compte = labelled(r"(?:num[ée]ro|n°)\s+(?:de|du)\s+compte", r"[[:alnum:]]{11}")?;
+        let cle_rib = labelled(r"cl[ée]\s+rib", r"\d{2}")?;
+
+        Some(RibFr::new(
+            &code_banque,
+            &code_guichet,
+            &numero_compte,
+            &cle_rib,
+        ))
+    }
+
+    // clé = 97 - ((89 x banque + 15 x guichet + 3 x compte) modulo 97)
+    // the letters of the account number are replaced by digits
+    pub fn expected_cle_rib(&self) -> String {
+        let compte = self
+            .numero_compte
+            .chars()
+            .map(letter_to_digit)
+            .collect::<String>();
+
+        let modulo = [
+            (&self.code_banque, 89),
+            (&self.code_guichet, 15),
+            (&compte, 3),
+        ]
+        .iter()
+        .map(|(number, factor)| mod_97(number) * factor)
+        .sum::<u64>()
+            % 97;
+
+        format!("{:02}", 97 - modulo)
+    }
+
+    pub fn bban(&self) -> String {
+        format!(
+            "{}{}{}{}",
+            self.code_banque, self.code_guichet, self.numero_compte, self.cle_rib
+        )
+    }
+
+    // the iban rebuilt from the components, if the clé RIB is valid
+    pub fn to_iban(&self) -> Option<Iban> {
+        if !self.cle_rib_valid {
+            return None;
+        }
+
+        let bban = self.bban();
+        let check_digits = 98 - mod_97(&format!("{}FR00", bban));
+
+        format!("FR{:02}{}", check_digits, bban)
+            .parse::<Iban>()
+            .ok()
+    }
+
+    // the differences between the iban and the table
+    pub fn inconsistencies(&self, iban: &str) -> Vec<String> {
+        let mut inconsistencies = vec![];
+
+        if !self.cle_rib_valid {
+            inconsistencies.push(format!(
+                "Invalid clé RIB: {}, expected {}",
+                self.cle_rib,
+                self.expected_cle_rib()
+            ));
+        }
+
+        let electronic_iban = iban.replace(' ', "");
+        if electronic_iban.starts_with("FR")
+            && electronic_iban.get(4..) != Some(self.bban().as_str())
+        {
+            inconsistencies.push(format!(
+                "IBAN {} does not match the RIB {} {} {} {}",
+                iban, self.code_banque, self.code_guichet, self.numero_compte, self.cle_rib
+            ));
+        }
+
+        inconsistencies
+    }
+}
+
+fn letter_to_digit(c: char) -> char {
+    match c {
+        'A' | 'J' => '1',
+        'B' | 'K' | 'S' => '2',
+        'C' | 'L' | 'T' => '3',
+        'D' | 'M' | 'U' => '4',
+        'E' | 'N' | 'V' => '5',
+        'F' | 'O' | 'W' => '6',
+        'G' | 'P' | 'X' => '7',
+        'H' | 'Q' | 'Y' => '8',
+        'I' | 'R' | 'Z' => '9',
+        _ => c,
+    }
+}
+
+// modulo 97 of a number written in base 36, as in the iban checksum
+fn mod_97(s: &str) -> u64 {
+    s.chars().filter_map(|c| c.to_digit(36)).fold(0, |acc, d| {
+        if d < 10 {
+            (acc * 10 + d as u64) % 97
+        } else {
+            (acc * 100 + d as u64) % 97
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cle_rib() {
+        let rib_fr = RibFr::new("30001", "00064", "49190095620", "88");
+        assert!(rib_fr.cle_rib_valid);
+        assert_eq!(
+            rib_fr.to_iban().unwrap().to_string(),
+            "FR76 3000 1000 6449 1900 9562 088"
+        );
+
+        let rib_fr = RibFr::new("30001", "00064", "49190095620", "87");
+        assert!(!rib_fr.cle_rib_valid);
+        assert_eq!(rib_fr.expected_cle_rib(), "88");
+        assert_eq!(rib_fr.to_iban(), None);
+    }
+
+    #[test]
+    fn test_find() {
+        let text = "Code Banque Code guichet N° du compte Clé RIB Domiciliation
+30001 00064 49190095620 88 4 Boulevard De La Fraternite";
+        assert_eq!(
+            RibFr::find(text),
+            Some(RibFr::new("30001", "00064", "49190095620", "88"))
+        );
+
+        let text = "Code Banque 30001
+Code guichet 00064
+Numéro de compte 49190095620
+Clé RIB 88";
+        assert_eq!(
+            RibFr::find(text),
+            Some(RibFr::new("30001", "00064", "49190095620", "88"))
+        );
+
+        assert_eq!(RibFr::find("IBAN DE89 3704 0044 0532 0130 00"), None);
+
+        let text = "Commande 12345 67890
+12345678901 12
+Code Banque Code guichet N° du compte Clé RIB
+30001 00064 49190095620 88";
+        assert_eq!(
+            RibFr::find(text),
+            Some(RibFr::new("30001", "00064", "49190095620", "88"))
+        );
+
+        let text = "Facture 12345 67890 ABCDEFGHIJK 12
+30001 00064 49190095620 88";
+        assert_eq!(
+            RibFr::find(text),
+            Some(RibFr::new("30001", "00064", "49190095620", "88"))
+        );
+
+        // without valid row, the first one
+        let text = "30001 00064 49190095620 87";
+        assert_eq!(
+            RibFr::find(text).map(|rib_fr| rib_fr.cle_rib_valid),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_inconsistencies() {
+        let rib_fr = RibFr::new("30001", "00064", "49190095620", "88");
+        assert!(rib_fr
+            .inconsistencies("FR76 3000 1000 6449 1900 9562 088")
+            .is_empty());
+
+        assert_eq!(
+            rib_fr.inconsistencies("FR76 3000 1000 6449 1900 9562 188"),
+            vec!["IBAN FR76 3000 1000 6449 1900 9562 188 does not match the RIB 30001 00064 49190095620 88"]
+        );
+    }
+}
@@ -0,0 +1,1422 @@
+use std::sync::LazyLock;
+
+use iban::Iban;
+use itertools::Itertools;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use self::{
+    bic_directory::BIC_DIRECTORY,
+    correction::{correct_iban, exact_iban},
+    domiciliation::{find_domiciliation, Domiciliation},
+    fr::RibFr,
+    profile::Profile,
+    provenance::{FieldProvenance, Provenance, Source},
+    role::{classify_roles, is_rib, IbanRole},
+    sepa::{iban_pattern, is_sepa_country, SEPA_COUNTRIES},
+    specimen::{is_specimen_bic, is_specimen_iban},
+    statement::{account_block, Statement},
+};
+use crate::{
+    epc::Epc,
+    fi_extract::{self, Institution},
+    layout::Layout,
+    text::{
+        address::find_titulaire_addr,
+        holder::Holder,
+        holder_match::{match_holder, HolderMatch},
+        simple_titulaire::find_simple_titulaire,
+    },
+};
+
+pub mod bic_directory;
+pub mod correction;
+pub mod domiciliation;
+pub mod fr;
+pub mod profile;
+pub mod provenance;
+pub mod role;
+pub mod sepa;
+pub mod specimen;
+pub mod statement;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Rib {
+    titulaire: Option<Vec<String>>,
+    // the titulaire lines parsed into holders and address
+    holder: Option<Holder>,
+    iban: String,
+    // whose account the iban is, from its labels in the document
+    iban_role: IbanRole,
+    bic: Option<String>,
+    // no bic is printed, it comes from the bank code of the iban
+    bic_inferred: bool,
+    // the bic belongs to the bank of the iban, see bic_directory.tsv
+    bic_matches_iban: Option<bool>,
+    bank_name: Option<String>,
+    // the bank in the ecb list, with its type, head office and country
+    institution: Option<Institution>,
+    // the layout of the bank, see profiles.json
+    profile: Option<String>,
+    // all the ibans found, the most likely first
+    iban_candidates: Vec<IbanCandidate>,
+    rib_fr: Option<RibFr>,
+    // differences between the iban and the printed rib
+    inconsistencies: Vec<String>,
+    domiciliation: Option<Domiciliation>,
+    // the bank of the iban is the one named in the domiciliation
+    bank_name_matches: Option<bool>,
+    // comparison with the expected holder, when given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    holder_match: Option<HolderMatch>,
+    provenance: Provenance,
+    // the iban or the bic is a known example, not real bank details
+    specimen: bool,
+    // the EPC payment qr code printed on the document
+    epc: Option<Epc>,
+    epc_matches_printed_iban: Option<bool>,
+    // the period and the closing balance, when a bank statement is sent
+    statement: Option<Statement>,
+    // the document is titled as a rib, only used to trust its EPC qr code
+    #[serde(skip)]
+    titled_rib: bool,
+}
+
+// where the iban was read
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbanSource {
+    TextLayer,
+    Ocrs,
+    Tesseract,
+    EpcQrCode,
+}
+
+impl IbanSource {
+    // only an ocr reading may confuse characters
+    fn is_ocr(&self) -> bool {
+        matches!(self, IbanSource::Ocrs | IbanSource::Tesseract)
+    }
+
+    fn confidence(&self) -> f64 {
+        match self {
+            IbanSource::TextLayer | IbanSource::EpcQrCode => 1.0,
+            IbanSource::Ocrs => 0.9,
+            IbanSource::Tesseract => 0.85,
+        }
+    }
+}
+
+// the fix applied to the read text to obtain a valid iban
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbanCorrection {
+    // characters confused by the ocr were substituted, see correction.rs
+    Substitutions,
+    // the iban was found once all the spaces removed
+    SpacesRemoved,
+    // the iban is a mix of two faulty readings
+    Combination,
+    // the iban is computed from the french rib table
+    RebuiltFromRib,
+}
+
+impl IbanCorrection {
+    fn confidence(&self) -> f64 {
+        match self {
+            // depends on the number of substitutions
+            IbanCorrection::Substitutions => 1.0,
+            IbanCorrection::SpacesRemoved => 0.9,
+            IbanCorrection::Combination => 0.6,
+            IbanCorrection::RebuiltFromRib => 0.8,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IbanCandidate {
+    pub iban: String,
+    pub source: IbanSource,
+    pub correction: Option<IbanCorrection>,
+    // number of characters substituted to obtain a valid iban
+    pub substitutions: usize,
+    pub confidence: f64,
+    pub role: IbanRole,
+}
+
+impl IbanCandidate {
+    fn new(iban: &Iban, source: IbanSource, correction: Option<IbanCorrection>) -> Self {
+        let confidence = round_confidence(
+            source.confidence() * correction.map(|c| c.confidence()).unwrap_or(1.0),
+        );
+
+        IbanCandidate {
+            iban: iban.to_string(),
+            source,
+            correction,
+            substitutions: 0,
+            confidence,
+            role: IbanRole::Unknown,
+        }
+    }
+
+    // each substitution lowers the confidence, as do the other
+    // solutions found with the same number of substitutions
+    fn with_substitutions(mut self, substitutions: usize, nb_solutions: usize) -> Self {
+        self.substitutions = substitutions;
+        self.confidence = round_confidence(
+            self.confidence * SUBSTITUTION_CONFIDENCE.powi(substitutions as i32)
+                / nb_solutions as f64,
+        );
+        self
+    }
+}
+
+const SUBSTITUTION_CONFIDENCE: f64 = 0.9;
+
+fn round_confidence(confidence: f64) -> f64 {
+    (confidence * 100.0).round() / 100.0
+}
+
+impl Rib {
+    // the first candidate is the primary iban,
+    // the bic and the rib table are looked for in the text.
+    // the provenance of the bic is the one of the text
+    pub fn from_candidates(
+        iban_candidates: Vec<IbanCandidate>,
+        titulaire: Option<Vec<String>>,
+        text: &str,
+        mut provenance: Provenance,
+    ) -> Option<Self> {
+        let iban_candidates = classify_roles(iban_candidates, text);
+        let iban = iban_candidates.first()?.iban.clone();
+        let iban_role = iban_candidates.first()?.role;
+        let bic = extract_bic_for_iban(text, &iban);
+        let rib_fr = RibFr::find(text);
+        let domiciliation = find_domiciliation(text);
+        let holder = titulaire.as_deref().and_then(Holder::parse);
+        let profile = Profile::detect(text, Some(&iban)).map(|p| p.name.clone());
+
+        if bic.is_none() {
+            provenance.bic = None;
+        }
+        if titulaire.is_none() {
+            provenance.titulaire = None;
+        }
+
+        let mut rib = Rib {
+            titulaire,
+            holder,
+            iban,
+            iban_role,
+            bic,
+            bic_inferred: false,
+            bic_matches_iban: None,
+            bank_name: None,
+            institution: None,
+            iban_candidates,
+            rib_fr,
+            inconsistencies: vec![],
+            domiciliation,
+            bank_name_matches: None,
+            holder_match: None,
+            provenance,
+            profile,
+            specimen: false,
+            epc: None,
+            epc_matches_printed_iban: None,
+            statement: None,
+            titled_rib: is_rib(text),
+        };
+        rib.check_iban_and_bic();
+
+        Some(rib)
+    }
+
+    // the fields derived from the iban and the bic: the bank, the consistency
+    // checks and the specimen flag. without bic, it is inferred from the iban
+    fn check_iban_and_bic(&mut self) {
+        self.institution = fi_extract::institution(&self.iban);
+        self.bank_name = self.institution.as_ref().map(|i| i.name.clone());
+        self.provenance.bank_name = self
+            .bank_name
+            .as_ref()
+            .map(|_| FieldProvenance::new(Source::BankDirectory, None, false));
+
+        self.inconsistencies = self
+            .rib_fr
+            .as_ref()
+            .map(|rib_fr| rib_fr.inconsistencies(&self.iban))
+            .unwrap_or_default();
+
+        self.bank_name_matches = match (&self.domiciliation, &self.bank_name) {
+            (Some(domiciliation), Some(bank_name)) => {
+                Some(domiciliation.matches_bank_name(bank_name))
+            }
+            _ => None,
+        };
+
+        self.specimen = is_specimen_iban(&self.iban)
+            || (!self.bic_inferred && self.bic.as_deref().is_some_and(is_specimen_bic));
+        if self.specimen {
+            log::info!("specimen rib: {}", self.iban);
+        }
+
+        if self.bic_inferred {
+            self.bic = None;
+            self.bic_inferred = false;
+        }
+
+        self.bic_matches_iban = self
+            .bic
+            .as_deref()
+            .and_then(|bic| BIC_DIRECTORY.bic_matches_iban(bic, &self.iban));
+
+        if self.bic.is_none() {
+            self.bic = BIC_DIRECTORY.by_iban(&self.iban).map(|i| i.bic.clone());
+            self.bic_inferred = self.bic.is_some();
+            self.provenance.bic = self
+                .bic
+                .as_ref()
+                .map(|_| FieldProvenance::new(Source::BankDirectory, None, false));
+        }
+    }
+
+    // the iban of the EPC qr code is read without ocr, it comes first.
+    // the printed iban is checked against it
+    pub fn with_epc(mut self, epc: Epc) -> Self {
+        let Ok(iban) = epc.iban.replace(" ", "").parse::<Iban>() else {
+            return self;
+        };
+
+        let matches = self.iban == epc.iban;
+        if !matches {
+            log::warn!(
+                "the printed iban {} differs from the EPC qr code {}",
+                self.iban,
+                epc.iban
+            );
+        }
+        self.epc_matches_printed_iban = Some(matches);
+
+        // the role of the same iban when printed
+        let printed_role = self
+            .iban_candidates
+            .iter()
+            .find(|c| c.iban == epc.iban)
+            .map(|c| c.role);
+
+        // the qr code of an invoice pays the creditor, it only gives
+        // the account of the holder on a rib or when printed as such
+        if self.is_rib() || printed_role == Some(IbanRole::AccountHolder) {
+            let role = printed_role.unwrap_or(IbanRole::AccountHolder);
+            self.promote_epc(&iban, role, epc);
+        } else {
+            if printed_role.is_none() {
+                let mut candidate = IbanCandidate::new(&iban, IbanSource::EpcQrCode, None);
+                candidate.role = IbanRole::Creditor;
+                self.iban_candidates.push(candidate);
+            }
+            self.epc = Some(epc);
+        }
+
+        self
+    }
+
+    // only the EPC qr code was read, there is no printed iban to compare.
+    // it is the account to pay, the one of a creditor
+    pub fn from_epc(epc: Epc) -> Option<Self> {
+        let iban = epc.iban.replace(" ", "").parse::<Iban>().ok()?;
+        let candidates = vec![IbanCandidate::new(&iban, IbanSource::EpcQrCode, None)];
+
+        let mut rib = Rib::from_candidates(candidates, None, "", Provenance::default())?;
+        rib.promote_epc(&iban, IbanRole::Creditor, epc);
+
+        Some(rib)
+    }
+
+    // a rib is titled so or prints the french rib table
+    fn is_rib(&self) -> bool {
+        self.titled_rib || self.rib_fr.is_some()
+    }
+
+    // the iban of the qr code replaces the printed one
+    fn promote_epc(&mut self, iban: &Iban, role: IbanRole, epc: Epc) {
+        let epc_provenance = FieldProvenance::new(Source::EpcQrCode, None, false);
+
+        let mut candidate = IbanCandidate::new(iban, IbanSource::EpcQrCode, None);
+        candidate.role = role;
+
+        self.iban_candidates.retain(|c| c.iban != epc.iban);
+        self.iban_candidates.insert(0, candidate);
+        self.iban = epc.iban.clone();
+        self.iban_role = role;
+        self.provenance.iban = Some(epc_provenance.clone());
+
+        if let Some(bic) = &epc.bic {
+            self.bic = Some(bic.clone());
+            self.bic_inferred = false;
+            self.provenance.bic = Some(epc_provenance.clone());
+        }
+
+        if self.titulaire.is_none() {
+            let titulaire = vec![epc.name.clone()];
+            self.holder = Holder::parse(&titulaire);
+            self.titulaire = Some(titulaire);
+            self.provenance.titulaire = Some(epc_provenance);
+        }
+
+        self.epc = Some(epc);
+        self.check_iban_and_bic();
+    }
+
+    pub fn match_holder(&mut self, expected: &str) {
+        let titulaire = self.titulaire.clone().unwrap_or_default();
+        self.holder_match = Some(match_holder(expected, self.holder.as_ref(), &titulaire));
+    }
+
+    pub fn parse(text: String) -> Option<Self> {
+        Rib::parse_with_layout(text, || None)
+    }
+
+    // the layout of the words is only built when the profile of the bank
+    // misses the holder in the columns of the text
+    pub fn parse_with_layout(
+        text: String,
+        layout: impl FnOnce() -> Option<Layout>,
+    ) -> Option<Self> {
+        Rib::parse_page(
+            text,
+            IbanSource::TextLayer,
+            FieldProvenance::text_layer(),
+            layout,
+        )
+    }
+
+    // the text of a whole page, from the text layer or read by an ocr engine
+    fn parse_page(
+        text: String,
+        source: IbanSource,
+        page: FieldProvenance,
+        layout: impl FnOnce() -> Option<Layout>,
+    ) -> Option<Self> {
+        let candidates = extract_iban_candidates(&text, source);
+
+        // the profile of the bank first, the generic heuristics as fallback
+        let profile = Profile::detect(&text, candidates.first().map(|c| c.iban.as_str()));
+
+        let candidates = match profile.map(|p| p.iban_candidates(&text, source)) {
+            Some(profile_candidates) if !profile_candidates.is_empty() => profile_candidates,
+            _ => candidates,
+        };
+
+        let titulaire = profile
+            .and_then(|p| {
+                p.find_holder(&text)
+                    .or_else(|| p.find_holder_in_layout(&layout()?))
+            })
+            .or_else(|| find_titulaire_addr(&text).map(|addr| addr.lines()))
+            .or_else(|| find_simple_titulaire(&text, 3));
+
+        let provenance = Provenance {
+            iban: Some(page.clone()),
+            bic: Some(page.clone()),
+            titulaire: Some(page),
+            bank_name: None,
+        };
+
+        Rib::from_candidates(candidates, titulaire, &text, provenance)
+    }
+
+    // only the headers of the pages are read for the account,
+    // the transactions are full of amounts, references and other ibans
+    pub fn parse_statement(
+        text: String,
+        source: IbanSource,
+        page: FieldProvenance,
+    ) -> Option<Self> {
+        let block = account_block(&text);
+        let mut rib = Rib::parse_page(block.clone(), source, page.clone(), || None)?;
+
+        // the first address of the header is often the one of the bank
+        if let Some(titulaire) = statement::find_holder(&block) {
+            rib.holder = Holder::parse(&titulaire);
+            rib.titulaire = Some(titulaire);
+            rib.provenance.titulaire = Some(page);
+        }
+
+        rib.statement = Some(Statement::parse(&text));
+        Some(rib)
+    }
+
+    pub fn is_statement(&self) -> bool {
+        self.statement.is_some()
+    }
+
+    // the statement of the first page completed by the text of the following ones
+    pub fn with_following_pages(mut self, text: &str) -> Self {
+        self.statement = self
+            .statement
+            .take()
+            .map(|statement| statement.followed_by(Statement::parse(text)));
+        self
+    }
+
+    // a statement or a rib
+    pub fn parse_document(text: String) -> Option<Self> {
+        Rib::parse_document_with_layout(text, || None)
+    }
+
+    pub fn parse_document_with_layout(
+        text: String,
+        layout: impl FnOnce() -> Option<Layout>,
+    ) -> Option<Self> {
+        if Statement::detect(&text) {
+            Rib::parse_statement(text, IbanSource::TextLayer, FieldProvenance::text_layer())
+        } else {
+            Rib::parse_with_layout(text, layout)
+        }
+    }
+}
+
+// compiled once, there is one regex per SEPA country
+static IBAN_REGEXES: LazyLock<Vec<Regex>> = LazyLock::new(|| iban_regexes(false));
+static LAX_IBAN_REGEXES: LazyLock<Vec<Regex>> = LazyLock::new(|| iban_regexes(true));
+
+fn iban_regexes(lax: bool) -> Vec<Regex> {
+    SEPA_COUNTRIES
+        .iter()
+        .map(|(country, length, bban)| {
+            Regex::new(&iban_pattern(country, *length, bban, lax)).unwrap()
+        })
+        .collect()
+}
+
+// all the iban looking strings of the text, in order of appearance
+fn find_iban_like(regexes: &[Regex], text: &str) -> Vec<String> {
+    let to_remove = Regex::new(r"[[[:space:]]|,]*").unwrap();
+
+    regexes
+        .iter()
+        .flat_map(|re| re.find_iter(text).map(|m| (m.start(), m.as_str())))
+        .sorted_by_key(|(start, _)| *start)
+        .map(|(_, x)| to_remove.replace_all(x, "").to_string())
+        .collect()
+}
+
+pub fn extract_iban(text: &str) -> Option<String> {
+    extract_iban_candidates(text, IbanSource::TextLayer)
+        .into_iter()
+        .next()
+        .map(|candidate| candidate.iban)
+}
+
+// the valid ibans of the text, the most likely first
+pub fn extract_iban_candidates(text: &str, source: IbanSource) -> Vec<IbanCandidate> {
+    let candidates = strict_candidates(text, source, None);
+    if !candidates.is_empty() {
+        return rank(candidates);
+    }
+
+    // sometimes the iban is written with weird space (credit_agricole_2.txt)
+    // so we try to match by removing all spaces
+    let text_without_spaces = text.replace(" ", "");
+    let candidates = strict_candidates(
+        &text_without_spaces,
+        source,
+        Some(IbanCorrection::SpacesRemoved),
+    );
+    if !candidates.is_empty() {
+        return rank(candidates);
+    }
+
+    let candidates = combination_candidates(text, source);
+    if !candidates.is_empty() {
+        return rank(candidates);
+    }
+
+    // the iban line is missing or unreadable, but the rib table is there
+    RibFr::find(text)
+        .and_then(|rib_fr| rib_fr.to_iban())
+        .map(|iban| {
+            vec![IbanCandidate::new(
+                &iban,
+                source,
+                Some(IbanCorrection::RebuiltFromRib),
+            )]
+        })
+        .unwrap_or_default()
+}
+
+fn strict_candidates(
+    text: &str,
+    source: IbanSource,
+    correction: Option<IbanCorrection>,
+) -> Vec<IbanCandidate> {
+    find_iban_like(&IBAN_REGEXES, text)
+        .iter()
+        .flat_map(|iban| {
+            let corrections = if source.is_ocr() {
+                correct_iban(iban)
+            } else {
+                exact_iban(iban)
+            };
+            let nb_solutions = corrections.len();
+
+            corrections.into_iter().map(move |x| {
+                if x.substitutions == 0 {
+                    IbanCandidate::new(&x.iban, source, correction)
+                } else {
+                    IbanCandidate::new(
+                        &x.iban,
+                        source,
+                        correction.or(Some(IbanCorrection::Substitutions)),
+                    )
+                    .with_substitutions(x.substitutions, nb_solutions)
+                }
+            })
+        })
+        .collect()
+}
+
+fn combination_candidates(text: &str, source: IbanSource) -> Vec<IbanCandidate> {
+    let lax_ibans = find_iban_like(&LAX_IBAN_REGEXES, text);
+
+    if lax_ibans.len() < 2 {
+        return vec![];
+    }
+
+    // we take the 2 first iban and count the number of different characters
+    let iban1 = lax_ibans[0].clone();
+    let iban2 = lax_ibans[1].clone();
+
+    let mut differences = Vec::new();
+
+    // Itérer sur les caractères des chaînes
+    for (index, (c1, c2)) in iban1.chars().zip(iban2.chars()).enumerate() {
+        if c1 != c2 {
+            differences.push(index);
+        }
+    }
+
+    // to many combinations
+    if differences.len() > 10 {
+        return vec![];
+    }
+
+    let mut combinations = Vec::new();
+    let num_differences = differences.len();
+    let num_combinations = 1 << num_differences; // 2^n
+
+    for i in 0..num_combinations {
+        let mut combo = iban1.to_string();
+        for (j, &diff_pos) in differences.iter().enumerate() {
+            if (i >> j) & 1 == 1 {
+                let c = iban2.chars().nth(diff_pos).unwrap();
+                combo.replace_range(diff_pos..diff_pos + 1, &c.to_string());
+            }
+        }
+        combinations.push(combo);
+    }
+
+    let found_ibans = combinations
+        .into_iter()
+        .filter_map(|x| x.parse::<Iban>().ok())
+        .unique()
+        .collect::<Vec<Iban>>();
+
+    // the more valid combinations, the less we trust each of them
+    let nb_found = found_ibans.len() as f64;
+
+    found_ibans
+        .iter()
+        .map(|iban| {
+            let mut candidate = IbanCandidate::new(iban, source, Some(IbanCorrection::Combination));
+            candidate.confidence = round_confidence(candidate.confidence / nb_found);
+            candidate
+        })
+        .collect()
+}
+
+// one candidate per iban, sorted by decreasing confidence
+// and then by order of appearance
+fn rank(candidates: Vec<IbanCandidate>) -> Vec<IbanCandidate> {
+    candidates
+        .into_iter()
+        .enumerate()
+        .sorted_by(|(i, a), (j, b)| b.confidence.total_cmp(&a.confidence).then(i.cmp(j)))
+        .map(|(_, candidate)| candidate)
+        .unique_by(|candidate| candidate.iban.clone())
+        .collect()
+}
+
+pub fn extract_bic(content: &str, country: &str) -> Option<String> {
+    let without_space = Regex::new(&format!(
+        r"[A-Z]{{4}}{}[A-Z0-9]{{2}}([A-Z0-9]{{3}})?",
+        country
+    ))
+    .unwrap();
+    let with_xxx_with_space =
+        Regex::new(&format!(r"[A-Z]{{4}}\s?{}\s?[A-Z0-9]{{2}}\s?XXX?", country)).unwrap();
+
+    // Helper to get unique matches
+    fn get_unique_matches(regex: &Regex, text: &str) -> Vec<String> {
+        let matches: Vec<String> = regex
+            .find_iter(text)
+            .map(|m| m.as_str().to_string())
+            // the iban label followed by the iban can look like a bic
+            .filter(|m| !m.starts_with("IBAN"))
+            .unique()
+            .collect();
+        matches.into_iter().collect()
+    }
+
+    let mut without_space_matches = get_unique_matches(&without_space, content);
+    log::trace!("without_space_matches: {:?}", without_space_matches);
+    if without_space_matches.len() == 1 {
+        return Some(without_space_matches.pop().unwrap());
+    }
+
+    let mut with_xxx_with_space_matches = get_unique_matches(&with_xxx_with_space, content);
+    log::trace!(
+        "with_xxx_with_space_matches: {:?}",
+        with_xxx_with_space_matches
+    );
+    if with_xxx_with_space_matches.len() == 1 {
+        return Some(with_xxx_with_space_matches.pop().unwrap());
+    }
+
+    // remove all spaces and try again
+    let whitespace_regex = Regex::new(r"\s+").unwrap();
+    let content_without_spaces = whitespace_regex.replace_all(content, "");
+    let mut joined_without_space_matches =
+        get_unique_matches(&without_space, &content_without_spaces);
+    log::trace!(
+        "joined_without_space_matches: {:?}",
+        joined_without_space_matches
+    );
+    if joined_without_space_matches.len() == 1 {
+        return Some(joined_without_space_matches.pop().unwrap());
+    }
+
+    // try known banks BICs
+    if country == "FR" {
+        let caisse_epargne_bic = Regex::new(r"CEPAFRPP[A-Z0-9]{3}").unwrap();
+        let mut caisse_epargne_bic_matches =
+            get_unique_matches(&caisse_epargne_bic, &content_without_spaces);
+        if caisse_epargne_bic_matches.len() == 1 {
+            return Some(caisse_epargne_bic_matches.pop().unwrap());
+        }
+    }
+
+    None
+}
+
+// the bic is looked for with the country of the iban first,
+// as accounts are usually held in the country of the bank.
+// otherwise a bic of another SEPA country is accepted after a bic label
+// or when it is in the directory: a word like MONSIEUR has the shape of a bic
+pub fn extract_bic_for_iban(content: &str, iban: &str) -> Option<String> {
+    let country: String = iban.chars().take(2).collect();
+
+    if let Some(bic) = extract_bic(content, &country) {
+        return Some(bic);
+    }
+
+    let bic = r"\b([A-Z]{4}([A-Z]{2})[A-Z0-9]{2}(?:[A-Z0-9]{3})?)\b";
+    let labelled_bic =
+        Regex::new(&format!(r"(?i:\b(?:bic|swift)\b)[^\n]{{0,30}}?{}", bic)).unwrap();
+    let any_bic = Regex::new(bic).unwrap();
+
+    let labelled = labelled_bic
+        .captures_iter(content)
+        .map(|caps| (caps[1].to_string(), caps[2].to_string()));
+    let known = any_bic
+        .captures_iter(content)
+        .filter(|caps| BIC_DIRECTORY.by_bic(&caps[1]).is_some())
+        .map(|caps| (caps[1].to_string(), caps[2].to_string()));
+
+    let bics = labelled
+        .chain(known)
+        .filter(|(_, bic_country)| *bic_country != country && is_sepa_country(bic_country))
+        .map(|(bic, _)| bic)
+        .unique()
+        .collect::<Vec<String>>();
+
+    if bics.len() == 1 {
+        bics.into_iter().next()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::{BBox, Line, Page, Word};
+    use provenance::Region;
+
+    #[test]
+    fn test_extract_iban() {
+        let iban = "FR76 3000 1000 6449 1900 9562 088";
+        assert_eq!(extract_iban(iban).unwrap(), iban);
+
+        let other_iban = "FR76 | 3000
+
+          1000 | 6449
+
+          1900 | 9562 | 088";
+        assert_eq!(extract_iban(other_iban).unwrap(), iban);
+
+        let iban_with_faults = "
+          FRTS 3000 1000 6449 1900 9562 088
+          FR76 3000 BOO0 6666 1900 9562 088
+        ";
+
+        assert_eq!(extract_iban(iban_with_faults).unwrap(), iban);
+    }
+
+    #[test]
+    fn test_extract_sepa_iban() {
+        let german_iban = "DE89 3704 0044 0532 0130 00";
+        assert_eq!(
+            extract_iban("IBAN: DE89 | 3704 | 0044 | 0532 | 0130 | 00").unwrap(),
+            german_iban
+        );
+
+        let belgian_iban = "BE68 5390 0754 7034";
+        assert_eq!(extract_iban("IBAN BE68539007547034").unwrap(), belgian_iban);
+
+        let spanish_iban = "ES91 2100 0418 4502 0005 1332";
+        assert_eq!(extract_iban(spanish_iban).unwrap(), spanish_iban);
+
+        // read by the ocr, the O of the check digits is a 0
+        let portuguese_iban = "PT50 0002 0123 1234 5678 9015 4";
+        assert_eq!(
+            extract_iban_candidates("Compte PT5O 0002 0123 1234 5678 9015 4", IbanSource::Ocrs)[0]
+                .iban,
+            portuguese_iban
+        );
+    }
+
+    #[test]
+    fn test_extract_iban_candidates() {
+        // an invoice with the account of the customer and the one of the company
+        let text = "
+          Prélevé sur : FR76 3000 1000 6449 1900 9562 088
+          Pour nous payer : DE89 3704 0044 0532 0130 00
+          Rappel : FR76 3000 1000 6449 1900 9562 088
+        ";
+
+        let candidates = extract_iban_candidates(text, IbanSource::Ocrs);
+        assert_eq!(
+            candidates
+                .iter()
+                .map(|c| c.iban.as_str())
+                .collect::<Vec<&str>>(),
+            vec![
+                "FR76 3000 1000 6449 1900 9562 088",
+                "DE89 3704 0044 0532 0130 00"
+            ]
+        );
+        assert_eq!(candidates[0].source, IbanSource::Ocrs);
+        assert_eq!(candidates[0].correction, None);
+        assert_eq!(candidates[0].confidence, 0.9);
+
+        // the corrected one comes after the exact one
+        let text = "FR7G 3000 1000 6449 1900 9562 088\nBE68 5390 0754 7034";
+        let candidates = extract_iban_candidates(text, IbanSource::Ocrs);
+        assert_eq!(candidates[0].iban, "BE68 5390 0754 7034");
+        assert_eq!(candidates[1].iban, IBAN);
+        assert_eq!(
+            candidates[1].correction,
+            Some(IbanCorrection::Substitutions)
+        );
+        assert_eq!(candidates[1].substitutions, 1);
+        assert!(candidates[1].confidence < 1.0);
+
+        // a text layer is never corrected, this checksum is wrong
+        let text = "IBAN FR76 1820 6000 3060 0018 2530 127";
+        assert!(extract_iban_candidates(text, IbanSource::TextLayer).is_empty());
+
+        let text = "IBAN FR7G 3000 1000 6449 1900 956Z 088";
+        let candidates = extract_iban_candidates(text, IbanSource::Ocrs);
+        assert_eq!(candidates[0].iban, IBAN);
+        assert_eq!(candidates[0].substitutions, 2);
+        assert_eq!(candidates[0].confidence, 0.73);
+
+        let iban_with_faults = "
+          FRTS 3000 1000 6449 1900 9562 088
+          FR76 3000 BOO0 6666 1900 9562 088
+        ";
+        let candidates = extract_iban_candidates(iban_with_faults, IbanSource::Tesseract);
+        assert_eq!(candidates[0].correction, Some(IbanCorrection::Combination));
+    }
+
+    #[test]
+    fn test_provenance() {
+        let rib = to_rib("tests/fixtures/rib/lcl.txt");
+        assert_eq!(
+            rib.provenance,
+            Provenance {
+                iban: Some(FieldProvenance::text_layer()),
+                bic: Some(FieldProvenance::text_layer()),
+                titulaire: Some(FieldProvenance::text_layer()),
+                bank_name: None,
+            }
+        );
+
+        let text = "IBAN FR05 1422 8000 3060 0018 2530 127";
+        let rib = Rib::parse(text.to_string()).unwrap();
+        assert_eq!(rib.provenance.bic, None);
+        assert_eq!(rib.provenance.titulaire, None);
+        assert_eq!(
+            rib.provenance.bank_name.unwrap().source,
+            Source::BankDirectory
+        );
+    }
+
+    #[test]
+    fn test_bic_directory() {
+        let rib = to_rib("tests/fixtures/rib/banque_populaire.txt");
+        assert_eq!(rib.bic_matches_iban, Some(true));
+
+        let rib = to_rib("tests/fixtures/rib/lcl.txt");
+        assert_eq!(rib.bic_matches_iban, Some(false));
+
+        let text = "IBAN FR27 1820 6000 3060 0018 2530 127";
+        let rib = Rib::parse(text.to_string()).unwrap();
+        assert_eq!(rib.bic, Some("AGRIFRPP".to_string()));
+        assert!(rib.bic_inferred);
+        assert_eq!(rib.bic_matches_iban, None);
+        assert_eq!(rib.provenance.bic.unwrap().source, Source::BankDirectory);
+    }
+
+    #[test]
+    fn test_institution() {
+        let text = "IBAN FR27 1820 6000 3060 0018 2530 127";
+        let rib = Rib::parse(text.to_string()).unwrap();
+        let institution = rib.institution.unwrap();
+
+        assert_eq!(institution.riad_code, "FR18206");
+        assert_eq!(institution.country, "FR");
+        assert_eq!(rib.bank_name, Some(institution.name));
+    }
+
+    fn epc(iban: &str, bic: Option<&str>) -> Epc {
+        Epc {
+            version: "002".to_string(),
+            bic: bic.map(|bic| bic.to_string()),
+            name: "Henri Matisse".to_string(),
+            iban: iban.to_string(),
+            amount: None,
+            purpose: None,
+            reference: None,
+            remittance: None,
+        }
+    }
+
+    #[test]
+    fn test_with_epc() {
+        let rib = to_rib("tests/fixtures/rib/lcl.txt").with_epc(epc(IBAN, Some("CRLYFRPP")));
+        assert_eq!(rib.epc_matches_printed_iban, Some(true));
+        assert_eq!(rib.iban_candidates.len(), 1);
+        assert_eq!(rib.iban_candidates[0].source, IbanSource::EpcQrCode);
+        assert_eq!(rib.provenance.iban.unwrap().source, Source::EpcQrCode);
+        assert_eq!(rib.titulaire, Some(vec_to_string(vec!["M MATISSE HENRI"])));
+
+        // the printed iban has been tampered with
+        let other_iban = "FR27 1820 6000 3060 0018 2530 127";
+        let rib = to_rib("tests/fixtures/rib/lcl.txt").with_epc(epc(other_iban, None));
+        assert_eq!(rib.epc_matches_printed_iban, Some(false));
+        assert_eq!(rib.iban, other_iban);
+        assert_eq!(rib.iban_candidates[1].iban, IBAN);
+        assert!(!rib.specimen);
+        assert!(!rib.inconsistencies.is_empty());
+        assert_eq!(rib.bic_matches_iban, Some(false));
+    }
+
+    #[test]
+    fn test_with_epc_invoice() {
+        // the qr code of an invoice pays the creditor, not the holder
+        let text = "FACTURE N° 2024-118\nVotre compte : FR76 3000 1000 6449 1900 9562 088\nMontant à régler : 120,00 EUR";
+        let creditor = "DE89 3704 0044 0532 0130 00";
+        let rib = Rib::parse(text.to_string())
+            .unwrap()
+            .with_epc(epc(creditor, None));
+
+        assert_eq!(rib.epc_matches_printed_iban, Some(false));
+        assert_eq!(rib.iban, IBAN);
+        assert_eq!(rib.iban_role, IbanRole::AccountHolder);
+        assert_eq!(rib.provenance.iban.unwrap().source, Source::TextLayer);
+        assert_eq!(rib.iban_candidates.len(), 2);
+        assert_eq!(rib.iban_candidates[1].iban, creditor);
+        assert_eq!(rib.iban_candidates[1].source, IbanSource::EpcQrCode);
+        assert_eq!(rib.iban_candidates[1].role, IbanRole::Creditor);
+        assert!(rib.epc.is_some());
+    }
+
+    #[test]
+    fn test_parse_with_layout() {
+        // the holder is a bit higher than its label, pdftotext -layout prints it on the line above
+        let text = "                              HENRI MATISSE
+TITULAIRE DU COMPTE :
+IBAN FR76 3000 1000 6449 1900 9562 088
+BIC CRLYFRPP";
+        let word = |text: &str, x0: f32, y0: f32, x1: f32| Word {
+            text: text.to_string(),
+            bbox: BBox::new(x0, y0, x1, y0 + 12.0),
+            confidence: None,
+        };
+        let layout = Layout {
+            pages: vec![Page::new(
+                595.0,
+                842.0,
+                vec![
+                    Line {
+                        words: vec![
+                            word("HENRI", 200.0, 98.0, 240.0),
+                            word("MATISSE", 243.0, 98.0, 300.0),
+                        ],
+                    },
+                    Line {
+                        words: vec![
+                            word("TITULAIRE", 50.0, 100.0, 110.0),
+                            word("DU", 113.0, 100.0, 130.0),
+                            word("COMPTE", 133.0, 100.0, 180.0),
+                            word(":", 182.0, 100.0, 185.0),
+                        ],
+                    },
+                ],
+            )],
+        };
+
+        let rib = Rib::parse_with_layout(text.to_string(), || Some(layout)).unwrap();
+        assert_eq!(rib.profile.as_deref(), Some("LCL"));
+        assert_eq!(rib.titulaire, Some(vec_to_string(vec!["HENRI MATISSE"])));
+
+        // the columns of the text alone miss it
+        let rib = Rib::parse(text.to_string()).unwrap();
+        assert_ne!(rib.titulaire, Some(vec_to_string(vec!["HENRI MATISSE"])));
+    }
+
+    #[test]
+    fn test_from_epc() {
+        let rib = Rib::from_epc(epc("FR27 1820 6000 3060 0018 2530 127", None)).unwrap();
+        assert_eq!(rib.epc_matches_printed_iban, None);
+        assert_eq!(rib.titulaire, Some(vec_to_string(vec!["Henri Matisse"])));
+        assert_eq!(rib.bic, Some("AGRIFRPP".to_string()));
+        assert!(rib.bic_inferred);
+        assert_eq!(rib.provenance.titulaire.unwrap().source, Source::EpcQrCode);
+        assert_eq!(rib.iban_role, IbanRole::Creditor);
+    }
+
+    #[test]
+    fn test_specimen() {
+        assert!(to_rib("tests/fixtures/rib/lcl.txt").specimen);
+
+        let text = "IBAN FR27 1820 6000 3060 0018 2530 127";
+        assert!(!Rib::parse(text.to_string()).unwrap().specimen);
+
+        let text = "IBAN FR27 1820 6000 3060 0018 2530 127\nBIC TESTFRP0";
+        assert!(Rib::parse(text.to_string()).unwrap().specimen);
+    }
+
+    #[test]
+    fn test_statement() {
+        let text = std::fs::read_to_string("tests/fixtures/rib/releve_de_compte.txt").unwrap();
+        let rib = Rib::parse_document(text).unwrap();
+
+        // the ibans of the transactions are ignored
+        assert_eq!(rib.iban, "FR27 1820 6000 3060 0018 2530 127");
+        assert_eq!(rib.iban_candidates.len(), 1);
+        assert_eq!(rib.titulaire, Some(vec!["MLE FRIDA KAHLO".to_string()]));
+        assert_eq!(
+            rib.statement
+                .and_then(|s| s.closing_balance)
+                .map(|b| b.amount),
+            Some(3243.6)
+        );
+        assert_eq!(rib.provenance.titulaire.unwrap().source, Source::TextLayer);
+    }
+
+    #[test]
+    fn test_statement_read_by_ocr() {
+        let text = std::fs::read_to_string("tests/fixtures/rib/releve_de_compte.txt").unwrap();
+        let page = FieldProvenance::new(Source::Ocrs, Some(Region::FullPage), true);
+        let rib = Rib::parse_statement(text, IbanSource::Ocrs, page.clone()).unwrap();
+
+        assert_eq!(rib.iban, "FR27 1820 6000 3060 0018 2530 127");
+        assert_eq!(rib.iban_candidates[0].source, IbanSource::Ocrs);
+        assert_eq!(rib.provenance.iban, Some(page.clone()));
+        assert_eq!(rib.provenance.titulaire, Some(page));
+        assert!(rib.is_statement());
+    }
+
+    #[test]
+    fn test_profile() {
+        // the address of the regional bank in the footer misleads the generic heuristics
+        let text = std::fs::read_to_string("tests/fixtures/rib/credit_agricole_2.txt").unwrap();
+        let text = format!(
+            "{}\nCrédit Agricole Atlantique Vendée\nRoute de Paris\n44949 NANTES CEDEX 9\n",
+            text
+        );
+        let generic = find_titulaire_addr(&text).unwrap().lines();
+        assert_eq!(generic.last().unwrap(), "44949 NANTES CEDEX 9");
+
+        let rib = Rib::parse(text).unwrap();
+        assert_eq!(rib.profile, Some("Crédit Agricole".to_string()));
+        assert_eq!(rib.titulaire, Some(vec_to_string(vec!["MME KAHLO FRIDA"])));
+        // printed as the rib table, the iban is found without removing the spaces
+        assert_eq!(rib.iban_candidates[0].correction, None);
+    }
+
+    #[test]
+    fn test_rib_fr() {
+        let rib = to_rib("tests/fixtures/rib/banque_populaire.txt");
+        assert_eq!(
+            rib.rib_fr,
+            Some(RibFr::new("30001", "00064", "49190095620", "88"))
+        );
+
+        // the iban line is damaged
+        let text = "IBAN FR76 3000 1000 6449 19?? 9562 088
+Code Banque Code guichet N° du compte Clé RIB
+30001 00064 49190095620 88";
+        let rib = Rib::parse(text.to_string()).unwrap();
+        assert_eq!(rib.iban, IBAN);
+        assert_eq!(
+            rib.iban_candidates[0].correction,
+            Some(IbanCorrection::RebuiltFromRib)
+        );
+
+        let text = "IBAN FR76 3000 1000 6449 1900 9562 088
+Banque Guichet Compte Clé RIB
+30001 00064 49190095621 88";
+        let rib = Rib::parse(text.to_string()).unwrap();
+        assert_eq!(rib.inconsistencies.len(), 2);
+    }
+
+    #[test]
+    fn test_extract_bic_for_iban() {
+        let text = "IBAN DE89 3704 0044 0532 0130 00\nBIC COBADEFFXXX";
+        assert_eq!(
+            extract_bic_for_iban(text, "DE89370400440532013000").unwrap(),
+            "COBADEFFXXX"
+        );
+
+        // a monegasque account held by a french bank
+        let text = "IBAN MC58 1122 2000 0101 2345 6789 030\nBIC AGRIFRPP883";
+        assert_eq!(
+            extract_bic_for_iban(text, "MC5811222000010123456789030").unwrap(),
+            "AGRIFRPP883"
+        );
+
+        // without label, a word with the shape of a bic is not one
+        let text = "Titulaire du compte : MONSIEUR HENRI MATISSE\nIBAN DE89 3704 0044 0532 0130 00";
+        assert_eq!(extract_bic_for_iban(text, "DE89370400440532013000"), None);
+    }
+
+    #[test]
+    fn test_rib_without_bic() {
+        let text = "Titulaire du compte : MONSIEUR HENRI MATISSE
+IBAN FR27 1820 6000 3060 0018 2530 127";
+        let rib = Rib::parse(text.to_string()).unwrap();
+
+        // the bic comes from the bank code of the iban
+        assert_eq!(rib.bic, Some("AGRIFRPP".to_string()));
+        assert!(rib.bic_inferred);
+        assert_eq!(
+            rib.provenance.bic.map(|p| p.source),
+            Some(Source::BankDirectory)
+        );
+    }
+
+    fn vec_to_string(v: Vec<&str>) -> Vec<String> {
+        v.iter().map(|x| x.to_string()).collect()
+    }
+
+    fn to_rib(path: &str) -> Rib {
+        let layout_text = std::fs::read_to_string(path).unwrap();
+        Rib::parse(layout_text).unwrap_or_else(|| {
+            panic!("Failed to parse RIB from file: {}", path);
+        })
+    }
+
+    fn test_file(path: &str, titulaire: Option<Vec<&str>>, iban: &str, bic: &str) {
+        let titulaire = titulaire.map(vec_to_string);
+        let text = std::fs::read_to_string(path).unwrap();
+        let rib = to_rib(path);
+
+        // the fields that depend on the layout of each fixture
+        assert_eq!(rib.iban_candidates[0].iban, iban);
+        assert_eq!(rib.iban_candidates[0].source, IbanSource::TextLayer);
+        assert_eq!(rib.iban_candidates[0].correction, None);
+        assert_eq!(rib.iban_candidates[0].role, IbanRole::AccountHolder);
+        // the rib table, when printed, is the one of the iban
+        assert!(rib
+            .rib_fr
+            .as_ref()
+            .is_none_or(|rib_fr| rib_fr.cle_rib_valid));
+        assert!(rib.inconsistencies.is_empty());
+        assert_eq!(rib.holder, titulaire.as_deref().and_then(Holder::parse));
+        assert_eq!(rib.domiciliation, find_domiciliation(&text));
+        assert_eq!(
+            rib.provenance.iban.as_ref().map(|p| p.source),
+            Some(Source::TextLayer)
+        );
+        assert_eq!(
+            rib.provenance.bic.as_ref().map(|p| p.source),
+            Some(Source::TextLayer)
+        );
+        assert_eq!(rib.provenance.titulaire.is_some(), titulaire.is_some());
+        assert_eq!(
+            rib.profile,
+            Profile::detect(&text, Some(iban)).map(|p| p.name.clone())
+        );
+        // the fixtures mix the bank code of the banque de france with other bics
+        assert_eq!(
+            rib.bic_matches_iban,
+            BIC_DIRECTORY.bic_matches_iban(bic, iban)
+        );
+        // only some fixtures are titled as a rib
+        assert_eq!(rib.titled_rib, is_rib(&text));
+
+        assert_eq!(
+            rib,
+            Rib {
+                titulaire,
+                holder: rib.holder.clone(),
+                iban: iban.to_string(),
+                iban_role: IbanRole::AccountHolder,
+                bic: Some(bic.to_string()),
+                bic_inferred: false,
+                bic_matches_iban: rib.bic_matches_iban,
+                bank_name: None,
+                institution: None,
+                iban_candidates: rib.iban_candidates.clone(),
+                rib_fr: rib.rib_fr.clone(),
+                inconsistencies: vec![],
+                domiciliation: rib.domiciliation.clone(),
+                bank_name_matches: None,
+                holder_match: None,
+                provenance: rib.provenance.clone(),
+                profile: rib.profile.clone(),
+                // every fixture uses the example iban
+                specimen: true,
+                epc: None,
+                epc_matches_printed_iban: None,
+                statement: None,
+                titled_rib: rib.titled_rib,
+            }
+        );
+    }
+
+    static IBAN: &str = "FR76 3000 1000 6449 1900 9562 088";
+
+    #[test]
+    fn rib_banque_populaire() {
+        let path = "tests/fixtures/rib/banque_populaire.txt";
+        let titulaire = Some(vec![
+            "M OU MME MATISSE HENRI",
+            "51 RUE BERNARD ROY",
+            "44100 NANTES",
+        ]);
+        let bic = "BDFEFRPPCCT";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_banque_populaire_2() {
+        let path = "tests/fixtures/rib/banque_populaire_2.txt";
+        let titulaire = Some(vec![
+            "M HENRI MATISSE OU MLLE",
+            "FRIDA KAHLO",
+            "31 AVENUE JULES RENARD",
+            "44800 ST HERBLAIN",
+        ]);
+        let bic = "BDFEFRPPCCT";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_banque_postale() {
+        let path = "tests/fixtures/rib/banque_postale.txt";
+        let titulaire = Some(vec![
+            "MR MATISSE HENRI",
+            "243 RUE DES GRIVES",
+            "44240 LA CHAPELLE SUR ERDRE",
+        ]);
+        let bic = "PSSTFRPPNTE";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_banque_postale_2() {
+        let path = "tests/fixtures/rib/banque_postale_2.txt";
+        let titulaire = Some(vec!["MLE FRIDA KHALO", "OU MR MATISSE HENRI"]);
+        let bic = "PSSTFRPPNTE";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_bourso() {
+        let path = "tests/fixtures/rib/bourso.txt";
+        let titulaire = Some(vec![
+            "Mlle Kahlo Frida",
+            "55 CHEMIN DU PETIT BOIS",
+            "44400 REZE",
+        ]);
+        let bic = "BOUS FRPP XXX";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_caisse_epargne() {
+        let path = "tests/fixtures/rib/caisse_epargne.txt";
+        let titulaire = Some(vec![
+            "MME KAHLO FRIDA OU M MATISSE",
+            "143 ALLEE DES SALICAIRES",
+            "44240 LA CHAPELLE SUR ERDRE",
+        ]);
+        let bic = "CEPAFRPP444";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_caisse_epargne_2() {
+        let path = "tests/fixtures/rib/caisse_epargne_2.txt";
+        let titulaire = Some(vec![
+            "M MATISSE HENRI",
+            "12 RUE VICTOR FORTUN",
+            "44400 REZE",
+        ]);
+        let bic = "CEPAFRPP444";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_caisse_epargne_3() {
+        let path = "tests/fixtures/rib/caisse_epargne_3.txt";
+        let titulaire = Some(vec![
+            "ML KHALO FRIDA OU M MATISSE H",
+            "35 RUE DU CEDRE",
+            "44240 LA CHAPELLE SUR ERDRE",
+        ]);
+        let bic = "CEPAFRPP444";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_credit_agricole() {
+        let path = "tests/fixtures/rib/credit_agricole.txt";
+        let titulaire = Some(vec![
+            "MR OU MME MATISSE",
+            "HENRI",
+            "32 RUE EDOUARD TRAVIES",
+            "44240 LA CHAPELLE SUR ERDRE",
+        ]);
+        let bic = "AGRIFRPP847";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_credit_agricole_2() {
+        let path = "tests/fixtures/rib/credit_agricole_2.txt";
+        let titulaire = Some(vec!["MME KAHLO FRIDA"]);
+        let bic = "AGRIFRPP847";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_credit_agricole_3() {
+        let path = "tests/fixtures/rib/credit_agricole_3.txt";
+        let titulaire = Some(vec![
+            "MLLE FRIDA KHALO",
+            "15 RUE MARYSE BASTIE",
+            "44230 ST SEBASTIEN SUR LOIRE",
+        ]);
+        let bic = "AGRIFRPP847";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_credit_mutuel() {
+        let path = "tests/fixtures/rib/credit_mutuel.txt";
+        let titulaire = Some(vec![
+            "M HENRI MATISSE",
+            "123 ALLEE DES ROSES",
+            "44640 LE PELLERIN",
+        ]);
+        let bic = "CMCIFR2A";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_credit_mutuel_2() {
+        let path = "tests/fixtures/rib/credit_mutuel_2.txt";
+        let titulaire = Some(vec![
+            "M OU MME MATISSE HENRI",
+            "54 RUE DE L HERONNIERE",
+            "44000 NANTES",
+        ]);
+        let bic = "CMBRFR2BXXX";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_fortuneo() {
+        let path = "tests/fixtures/rib/fortuneo.txt";
+        let titulaire = Some(vec!["Madame Khalo Frida ou Monsieur Matisse Henri"]);
+        let bic = "FTNOFRP1XXX";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_lcl() {
+        let path = "tests/fixtures/rib/lcl.txt";
+        let titulaire = Some(vec!["M MATISSE HENRI"]);
+        let bic = "CRLYFRPP";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_lcl_2() {
+        let path = "tests/fixtures/rib/lcl_2.txt";
+        let titulaire = Some(vec!["MLLE FRIDA KHALO"]);
+        let bic = "CRLYFRPP";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_sg() {
+        let path = "tests/fixtures/rib/sg.txt";
+        let titulaire = Some(vec![
+            "Mlle Frida Khalo",
+            "117 rue des bourdonnieres 204 batiment c",
+            "44200 Nantes",
+        ]);
+        let bic = "SOGEFRPP";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_sg_2() {
+        let path = "tests/fixtures/rib/sg_2.txt";
+        let titulaire = Some(vec![
+            "SAS HENRI MATISSE",
+            "18 RUE SADI CARNOT",
+            "92120 MONTROUGE",
+        ]);
+        let bic = "SOGEFRPP";
+        test_file(path, titulaire, IBAN, bic);
+    }
+
+    #[test]
+    fn rib_orange() {
+        let path = "tests/fixtures/rib/orange.txt";
+        let titulaire = Some(vec!["M Matisse Henri"]);
+        let bic = "GPBAFRPPXXX";
+        test_file(path, titulaire, IBAN, bic);
+    }
+}
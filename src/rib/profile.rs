@@ -0,0 +1,481 @@
+use std::sync::LazyLock;
+
+use iban::Iban;
+use regex::Regex;
+use serde::Deserialize;
+
+use super::{extract_bic, extract_bic_for_iban, IbanCandidate, IbanSource};
+use crate::{
+    layout::Layout,
+    text::column::{column, segments},
+};
+
+// the layouts of the banks, tried before the generic heuristics
+static PROFILES_JSON: &str = include_str!("profiles.json");
+static PROFILES: LazyLock<Vec<Profile>> =
+    LazyLock::new(|| serde_json::from_str(PROFILES_JSON).unwrap());
+// lines without the holder column between two lines of the holder block
+const MAX_GAP: usize = 2;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HolderPosition {
+    // the holder is on the same line as the label, or on the next one
+    Right,
+    // the holder is in the column of the label, on the following lines
+    Below,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    // the 5 digits of the bank in a french iban
+    pub bank_codes: Vec<String>,
+    // the first 4 letters of the bic
+    pub bic_prefixes: Vec<String>,
+    // regex of a text printed by the bank, when neither its code nor its bic is
+    pub text_pattern: Option<String>,
+    // regex of the label preceding the holder, case insensitive
+    pub holder_label: String,
+    pub holder_position: HolderPosition,
+    // maximum number of lines of the holder block
+    pub holder_lines: usize,
+    // regex of the labels ending the holder block
+    pub holder_stop: String,
+    // the length of each group of characters of the printed iban
+    pub iban_groups: Vec<usize>,
+}
+
+impl Profile {
+    // by the bank code of the iban, then by the bic prefix, then by the text
+    pub fn detect(text: &str, iban: Option<&str>) -> Option<&'static Profile> {
+        if let Some(bank_code) = iban.and_then(bank_code) {
+            if let Some(profile) = PROFILES.iter().find(|p| p.bank_codes.contains(&bank_code)) {
+                return Some(profile);
+            }
+        }
+
+        let bic = match iban {
+            Some(iban) => extract_bic_for_iban(text, iban),
+            None => extract_bic(text, "FR"),
+        }
+        .map(|bic| bic.replace(" ", ""));
+
+        if let Some(profile) = bic.and_then(|bic| {
+            PROFILES
+                .iter()
+                .find(|p| p.bic_prefixes.iter().any(|prefix| bic.starts_with(prefix)))
+        }) {
+            return Some(profile);
+        }
+
+        PROFILES.iter().find(|p| {
+            p.text_pattern
+                .as_ref()
+                .is_some_and(|pattern| Regex::new(pattern).unwrap().is_match(text))
+        })
+    }
+
+    // the ibans printed with the grouping of the bank
+    pub fn iban_candidates(&self, text: &str, source: IbanSource) -> Vec<IbanCandidate> {
+        let groups = self
+            .iban_groups
+            .iter()
+            .enumerate()
+            .map(|(i, len)| match i {
+                0 => format!(r"[A-Z]{{2}}\d{{2}}[A-Z0-9]{{{}}}", len.saturating_sub(4)),
+                _ => format!(r"[A-Z0-9]{{{}}}", len),
+            })
+            .collect::<Vec<String>>()
+            .join(r"[ |]+");
+        let iban_regex = Regex::new(&format!(r"\b{}\b", groups)).unwrap();
+
+        iban_regex
+            .find_iter(text)
+            .filter_map(|m| m.as_str().replace([' ', '|'], "").parse::<Iban>().ok())
+            .map(|iban| IbanCandidate::new(&iban, source, None))
+            .fold(vec![], |mut candidates, candidate| {
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
+                candidates
+            })
+    }
+
+    // the lines of the holder block, following the label
+    pub fn find_holder(&self, text: &str) -> Option<Vec<String>> {
+        let label = Regex::new(&format!("(?i){}", self.holder_label)).unwrap();
+        let stop = Regex::new(&self.holder_stop).unwrap();
+        let postal_code = Regex::new(r"^\d{5}\s").unwrap();
+
+        let m = label.find(text)?;
+        let lines = text.lines().collect::<Vec<&str>>();
+        let line_index = text[..m.end()].matches('\n').count();
+        let start_col = column(text, m.start());
+        let end_col = column(text, m.end());
+
+        let mut holder = vec![];
+
+        // the columns where the holder is expected, none to take the first one
+        // from the column of the label
+        let mut span = match self.holder_position {
+            HolderPosition::Below => Some((start_col, end_col)),
+            HolderPosition::Right => {
+                let rest = lines[line_index].chars().skip(end_col).collect::<String>();
+                match segments(&rest).into_iter().next() {
+                    Some((start, end, segment)) => {
+                        holder.push(segment);
+                        Some((end_col + start, end_col + end))
+                    }
+                    None => None,
+                }
+            }
+        };
+
+        let mut gap = 0;
+        for line in lines.iter().skip(line_index + 1) {
+            if holder.len() >= self.holder_lines {
+                break;
+            }
+
+            let segment = segments(line)
+                .into_iter()
+                .find(|(start, end, _)| match span {
+                    Some((s, e)) => *start < e && *end > s,
+                    None => *start >= start_col,
+                });
+
+            // the lines of the block may be spaced out, or interleaved
+            // with the lines of another column
+            let Some((start, end, segment)) = segment else {
+                gap += 1;
+                if holder.is_empty() || gap <= MAX_GAP {
+                    continue;
+                }
+                break;
+            };
+            gap = 0;
+
+            if stop.is_match(&segment) {
+                break;
+            }
+
+            span = span.or(Some((start, end)));
+            holder.push(segment.clone());
+
+            // the address ends with the postal code and the city
+            if postal_code.is_match(&segment) {
+                break;
+            }
+        }
+
+        if holder.is_empty() {
+            None
+        } else {
+            Some(holder)
+        }
+    }
+
+    // the same search on the geometry of the words rather than on the columns of the text
+    pub fn find_holder_in_layout(&self, layout: &Layout) -> Option<Vec<String>> {
+        let label = Regex::new(&format!("(?i){}", self.holder_label)).unwrap();
+        let stop = Regex::new(&self.holder_stop).unwrap();
+        let postal_code = Regex::new(r"^\d{5}\s").unwrap();
+
+        layout.pages.iter().find_map(|page| {
+            let segment = page.find(&label)?;
+            let bbox = segment.bbox()?;
+
+            let lines = match self.holder_position {
+                HolderPosition::Below => page.column_below(&bbox, self.holder_lines),
+                HolderPosition::Right => {
+                    // the holder may follow the label in the same segment
+                    let rest = label.replace(&segment.text(), "").trim().to_string();
+                    let first = Some(rest)
+                        .filter(|rest| !rest.is_empty())
+                        .or_else(|| page.right_of(&bbox));
+
+                    first
+                        .into_iter()
+                        .chain(page.column_below(&bbox, self.holder_lines))
+                        .collect()
+                }
+            };
+
+            let mut holder = vec![];
+            for line in lines.into_iter().take(self.holder_lines) {
+                if stop.is_match(&line) {
+                    break;
+                }
+                let is_postal_code = postal_code.is_match(&line);
+                holder.push(line);
+
+                // the address ends with the postal code and the city
+                if is_postal_code {
+                    break;
+                }
+            }
+
+            if holder.is_empty() {
+                None
+            } else {
+                Some(holder)
+            }
+        })
+    }
+}
+
+fn bank_code(iban: &str) -> Option<String> {
+    let iban = iban.replace(" ", "");
+    if !iban.starts_with("FR") {
+        return None;
+    }
+    iban.get(4..9).map(|code| code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::{BBox, Line, Page, Word};
+
+    fn profile(name: &str) -> &'static Profile {
+        PROFILES.iter().find(|p| p.name == name).unwrap()
+    }
+
+    fn holder_in_file(path: &str) -> Option<Vec<String>> {
+        let text = std::fs::read_to_string(path).unwrap();
+        Profile::detect(&text, None)?.find_holder(&text)
+    }
+
+    #[test]
+    fn test_detect() {
+        let text = std::fs::read_to_string("tests/fixtures/rib/sg_2.txt").unwrap();
+        assert_eq!(
+            Profile::detect(&text, None),
+            Some(profile("Société Générale"))
+        );
+
+        let text = "IBAN FR76 3000 2005 5000 0015 7845 Z02";
+        assert_eq!(
+            Profile::detect(text, Some("FR7630002005500000157845Z02")),
+            Some(profile("LCL"))
+        );
+
+        // a caisse régionale, without bic
+        let text = "IBAN FR27 1820 6000 3060 0018 2530 127";
+        assert_eq!(
+            Profile::detect(text, Some("FR2718206000306000182530127")),
+            Some(profile("Crédit Agricole"))
+        );
+
+        let text = std::fs::read_to_string("tests/fixtures/rib/orange.txt").unwrap();
+        assert_eq!(Profile::detect(&text, None), Some(profile("Orange Bank")));
+
+        // the fixtures print the bank code and the bic of the banque de france,
+        // the bank is recognized by its name or its layout
+        let text = std::fs::read_to_string("tests/fixtures/rib/banque_populaire.txt").unwrap();
+        assert_eq!(
+            Profile::detect(&text, Some("FR7630001000644919009562088")),
+            Some(profile("Banque Populaire"))
+        );
+
+        let text = std::fs::read_to_string("tests/fixtures/rib/caisse_epargne_3.txt").unwrap();
+        assert_eq!(
+            Profile::detect(&text, Some("FR7630001000644919009562088")),
+            Some(profile("Caisse d'Épargne"))
+        );
+
+        let text = "IBAN FR76 3000 1000 6449 1900 9562 088";
+        assert_eq!(Profile::detect(text, None), None);
+    }
+
+    #[test]
+    fn test_find_holder() {
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/banque_postale_2.txt"),
+            Some(vec![
+                "MLE FRIDA KHALO".to_string(),
+                "OU MR MATISSE HENRI".to_string()
+            ])
+        );
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/lcl.txt"),
+            Some(vec!["M MATISSE HENRI".to_string()])
+        );
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/sg_2.txt"),
+            Some(vec![
+                "SAS HENRI MATISSE".to_string(),
+                "18 RUE SADI CARNOT".to_string(),
+                "92120 MONTROUGE".to_string()
+            ])
+        );
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/banque_populaire.txt"),
+            Some(vec![
+                "M OU MME MATISSE HENRI".to_string(),
+                "51 RUE BERNARD ROY".to_string(),
+                "44100 NANTES".to_string()
+            ])
+        );
+        // the lines of the other column come between the lines of the holder
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/banque_populaire_2.txt"),
+            Some(vec![
+                "M HENRI MATISSE OU MLLE".to_string(),
+                "FRIDA KAHLO".to_string(),
+                "31 AVENUE JULES RENARD".to_string(),
+                "44800 ST HERBLAIN".to_string()
+            ])
+        );
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/caisse_epargne.txt"),
+            Some(vec![
+                "MME KAHLO FRIDA OU M MATISSE".to_string(),
+                "143 ALLEE DES SALICAIRES".to_string(),
+                "44240 LA CHAPELLE SUR ERDRE".to_string()
+            ])
+        );
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/caisse_epargne_2.txt"),
+            Some(vec![
+                "M MATISSE HENRI".to_string(),
+                "12 RUE VICTOR FORTUN".to_string(),
+                "44400 REZE".to_string()
+            ])
+        );
+        // nothing after the label, the holder is the first segment below from its column
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/credit_mutuel.txt"),
+            Some(vec![
+                "M HENRI MATISSE".to_string(),
+                "123 ALLEE DES ROSES".to_string(),
+                "44640 LE PELLERIN".to_string()
+            ])
+        );
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/credit_mutuel_2.txt"),
+            Some(vec![
+                "M OU MME MATISSE HENRI".to_string(),
+                "54 RUE DE L HERONNIERE".to_string(),
+                "44000 NANTES".to_string()
+            ])
+        );
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/orange.txt"),
+            Some(vec!["M Matisse Henri".to_string()])
+        );
+        assert_eq!(
+            holder_in_file("tests/fixtures/rib/bourso.txt"),
+            Some(vec![
+                "Mlle Kahlo Frida".to_string(),
+                "55 CHEMIN DU PETIT BOIS".to_string(),
+                "44400 REZE".to_string()
+            ])
+        );
+    }
+
+    // a word with its left and right edges
+    type Edges<'a> = (&'a str, f32, f32);
+
+    // one line per row of words, at the given height
+    fn layout(rows: &[(f32, &[Edges])]) -> Layout {
+        let lines = rows
+            .iter()
+            .map(|(y, words)| Line {
+                words: words
+                    .iter()
+                    .map(|(text, x0, x1)| Word {
+                        text: text.to_string(),
+                        bbox: BBox::new(*x0, *y, *x1, y + 12.0),
+                        confidence: None,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Layout {
+            pages: vec![Page::new(595.0, 842.0, lines)],
+        }
+    }
+
+    #[test]
+    fn test_find_holder_in_layout() {
+        // the holder is a bit higher than its label, pdftotext -layout prints it on the line above
+        let lcl = layout(&[
+            (
+                98.0,
+                &[
+                    ("M", 200.0, 210.0),
+                    ("MATISSE", 213.0, 260.0),
+                    ("HENRI", 263.0, 300.0),
+                ],
+            ),
+            (
+                100.0,
+                &[
+                    ("TITULAIRE", 50.0, 110.0),
+                    ("DU", 113.0, 130.0),
+                    ("COMPTE", 133.0, 180.0),
+                ],
+            ),
+            (130.0, &[("IBAN", 50.0, 80.0), ("FR76", 100.0, 130.0)]),
+        ]);
+        assert_eq!(
+            profile("LCL").find_holder_in_layout(&lcl),
+            Some(vec!["M MATISSE HENRI".to_string()])
+        );
+
+        let societe_generale = layout(&[
+            (
+                100.0,
+                &[
+                    ("Titulaire", 300.0, 350.0),
+                    ("du", 353.0, 365.0),
+                    ("compte", 368.0, 410.0),
+                ],
+            ),
+            (
+                114.0,
+                &[
+                    ("SAS", 300.0, 320.0),
+                    ("HENRI", 323.0, 360.0),
+                    ("MATISSE", 363.0, 410.0),
+                ],
+            ),
+            (
+                128.0,
+                &[
+                    ("18", 300.0, 312.0),
+                    ("RUE", 315.0, 335.0),
+                    ("CARNOT", 338.0, 380.0),
+                ],
+            ),
+            (
+                142.0,
+                &[("92120", 300.0, 335.0), ("MONTROUGE", 338.0, 400.0)],
+            ),
+            (156.0, &[("IBAN", 300.0, 330.0)]),
+        ]);
+        assert_eq!(
+            profile("Société Générale").find_holder_in_layout(&societe_generale),
+            Some(vec![
+                "SAS HENRI MATISSE".to_string(),
+                "18 RUE CARNOT".to_string(),
+                "92120 MONTROUGE".to_string()
+            ])
+        );
+
+        assert_eq!(profile("Fortuneo").find_holder_in_layout(&lcl), None);
+    }
+
+    #[test]
+    fn test_iban_candidates() {
+        let text = std::fs::read_to_string("tests/fixtures/rib/credit_agricole_2.txt").unwrap();
+        let candidates = profile("Crédit Agricole").iban_candidates(&text, IbanSource::TextLayer);
+
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].iban, "FR76 3000 1000 6449 1900 9562 088");
+        assert_eq!(candidates[0].correction, None);
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use super::IbanSource;
+
+// how the text of the field was obtained
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    TextLayer,
+    Ocrs,
+    Tesseract,
+    // the EPC payment qr code
+    EpcQrCode,
+    // the bank name is looked up from the bank code of the iban
+    BankDirectory,
+}
+
+impl From<IbanSource> for Source {
+    fn from(source: IbanSource) -> Self {
+        match source {
+            IbanSource::TextLayer => Source::TextLayer,
+            IbanSource::Ocrs => Source::Ocrs,
+            IbanSource::Tesseract => Source::Tesseract,
+            IbanSource::EpcQrCode => Source::EpcQrCode,
+        }
+    }
+}
+
+// the part of the image which was read, see shapes.rs for the masks
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Region {
+    FullPage,
+    IbanMask,
+    NarrowIbanMask,
+    RotatedIbanMask,
+    AddressMask,
+    RightAlignAddressMask,
+    TitulaireMask,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FieldProvenance {
+    pub source: Source,
+    pub region: Option<Region>,
+    // the image was cleaned before the ocr, see image_utils::clean_image
+    pub cleaned_image: bool,
+    // between 0 and 1, when the engine gives one
+    pub engine_confidence: Option<f64>,
+}
+
+impl FieldProvenance {
+    pub fn new(source: Source, region: Option<Region>, cleaned_image: bool) -> Self {
+        FieldProvenance {
+            source,
+            region,
+            cleaned_image,
+            engine_confidence: None,
+        }
+    }
+
+    pub fn text_layer() -> Self {
+        FieldProvenance::new(Source::TextLayer, Some(Region::FullPage), false)
+    }
+
+    pub fn with_engine_confidence(mut self, engine_confidence: Option<f64>) -> Self {
+        self.engine_confidence = engine_confidence;
+        self
+    }
+}
+
+// where each field of the rib comes from
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Provenance {
+    pub iban: Option<FieldProvenance>,
+    pub bic: Option<FieldProvenance>,
+    pub titulaire: Option<FieldProvenance>,
+    pub bank_name: Option<FieldProvenance>,
+}
@@ -0,0 +1,223 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::IbanCandidate;
+use crate::text::patch::Patch;
+
+// whose account it is, from the labels around the iban.
+// an invoice or a payslip also prints the accounts of the creditor or of the employer
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IbanRole {
+    AccountHolder,
+    Creditor,
+    Debtor,
+    #[default]
+    Unknown,
+}
+
+impl IbanRole {
+    // the account of the applicant first, between candidates as likely
+    fn priority(&self) -> u8 {
+        match self {
+            IbanRole::AccountHolder => 0,
+            IbanRole::Unknown => 1,
+            IbanRole::Debtor => 2,
+            IbanRole::Creditor => 3,
+        }
+    }
+}
+
+// the lines above the iban where a label is looked for
+const CONTEXT_LINES: usize = 3;
+
+static CREDITOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(cr[ée]ancier|b[ée]n[ée]ficiaire|creditor|beneficiary|payee|[àa] l.ordre de|r[èe]glement par virement|(payer|paiement|payable) par virement|fournisseur|prestataire|vendeur)").unwrap()
+});
+static DEBTOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(d[ée]biteur|debtor|payeur|compte [àa] d[ée]biter|pr[ée]lev[ée] sur|employeur|employer|[ée]metteur du virement)").unwrap()
+});
+static ACCOUNT_HOLDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(titulaire|account (owner|holder)|intitul[ée] du compte|votre compte|your account)",
+    )
+    .unwrap()
+});
+// without label, the ibans of a rib are the one of its holder
+static RIB_TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)(relev[ée] d.identit[ée]|\bR\.?I\.?B\b)").unwrap());
+
+// sets the role of each candidate. the most likely iban stays first,
+// the role only breaks the ties of confidence
+pub fn classify_roles(candidates: Vec<IbanCandidate>, text: &str) -> Vec<IbanCandidate> {
+    let is_rib = is_rib(text);
+
+    let mut candidates = candidates
+        .into_iter()
+        .map(|mut candidate| {
+            candidate.role = match role(text, &candidate.iban) {
+                IbanRole::Unknown if is_rib => IbanRole::AccountHolder,
+                role => role,
+            };
+            candidate
+        })
+        .collect::<Vec<IbanCandidate>>();
+
+    candidates.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then(a.role.priority().cmp(&b.role.priority()))
+    });
+    candidates
+}
+
+// the document is titled relevé d'identité bancaire
+pub fn is_rib(text: &str) -> bool {
+    RIB_TITLE.is_match(text)
+}
+
+pub fn role(text: &str, iban: &str) -> IbanRole {
+    let lines = text.lines().collect::<Vec<&str>>();
+
+    let Some((index, start, end)) = locate(&lines, iban) else {
+        return IbanRole::Unknown;
+    };
+
+    let labels = Regex::new(&format!(
+        "{}|{}|{}",
+        CREDITOR.as_str(),
+        DEBTOR.as_str(),
+        ACCOUNT_HOLDER.as_str()
+    ))
+    .unwrap();
+
+    // the column of the iban, up to the nearest label
+    let patch = Patch::extract(&lines, index, &labels, start, end, true, CONTEXT_LINES);
+
+    patch
+        .lines_with_context()
+        .iter()
+        .rev()
+        .take(CONTEXT_LINES + 1)
+        .find_map(|(content, context)| line_role(&format!("{} {}", context, content)))
+        .unwrap_or_default()
+}
+
+// a line naming both sides, as "vos créanciers et débiteurs", is not a label
+fn line_role(line: &str) -> Option<IbanRole> {
+    match (CREDITOR.is_match(line), DEBTOR.is_match(line)) {
+        (true, false) => Some(IbanRole::Creditor),
+        (false, true) => Some(IbanRole::Debtor),
+        (true, true) => None,
+        (false, false) if ACCOUNT_HOLDER.is_match(line) => Some(IbanRole::AccountHolder),
+        _ => None,
+    }
+}
+
+// the line and the columns of the iban, whatever its spacing
+fn locate(lines: &[&str], iban: &str) -> Option<(usize, usize, usize)> {
+    let pattern = iban
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| regex::escape(&c.to_string()))
+        .collect::<Vec<String>>()
+        .join(r"[ |]*");
+    let iban_regex = Regex::new(&pattern).unwrap();
+
+    lines.iter().enumerate().find_map(|(index, line)| {
+        let m = iban_regex.find(line)?;
+        let start = line[..m.start()].chars().count();
+        let end = start + m.as_str().chars().count() - 1;
+        Some((index, start, end))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rib::{extract_iban_candidates, IbanCandidate, IbanSource};
+
+    const INVOICE: &str = "FACTURE N° 2024-001
+Ateliers Matisse
+
+Règlement par virement à l'ordre de Ateliers Matisse
+IBAN : FR27 1820 6000 3060 0018 2530 127
+
+Client : Frida Kahlo
+Compte à débiter : FR05 1422 8000 3060 0018 2530 127
+";
+
+    #[test]
+    fn test_role() {
+        assert_eq!(
+            role(INVOICE, "FR27 1820 6000 3060 0018 2530 127"),
+            IbanRole::Creditor
+        );
+        assert_eq!(
+            role(INVOICE, "FR05 1422 8000 3060 0018 2530 127"),
+            IbanRole::Debtor
+        );
+        assert_eq!(
+            role(INVOICE, "FR76 3000 1000 6449 1900 9562 088"),
+            IbanRole::Unknown
+        );
+    }
+
+    #[test]
+    fn test_payslip() {
+        let text = "BULLETIN DE PAIE
+Employeur : Ateliers Matisse                 Banque de l'employeur
+                                             FR27 1820 6000 3060 0018 2530 127
+Net payé par virement sur votre compte
+FR05 1422 8000 3060 0018 2530 127
+";
+        let candidates = classify_roles(extract_iban_candidates(text, IbanSource::TextLayer), text);
+
+        assert_eq!(candidates[0].iban, "FR05 1422 8000 3060 0018 2530 127");
+        assert_eq!(candidates[0].role, IbanRole::AccountHolder);
+        assert_eq!(candidates[1].role, IbanRole::Debtor);
+    }
+
+    #[test]
+    fn test_classify_roles_confidence() {
+        let text = "Titulaire du compte
+FR76 3000 1000 6449 1900 9562 088
+
+Conditions générales
+Article 1
+Article 2
+Article 3
+DE89 3704 0044 0532 0130 00
+";
+        // the iban of the holder needed 3 substitutions, the other one is read as is
+        let holder = IbanCandidate::new(
+            &"FR7630001000644919009562088".parse().unwrap(),
+            IbanSource::Ocrs,
+            Some(crate::rib::IbanCorrection::Substitutions),
+        )
+        .with_substitutions(3, 1);
+        let clean = IbanCandidate::new(
+            &"DE89370400440532013000".parse().unwrap(),
+            IbanSource::Ocrs,
+            None,
+        );
+
+        let candidates = classify_roles(vec![holder, clean], text);
+
+        assert_eq!(candidates[0].iban, "DE89 3704 0044 0532 0130 00");
+        assert_eq!(candidates[0].role, IbanRole::Unknown);
+        assert_eq!(candidates[1].role, IbanRole::AccountHolder);
+        assert!(candidates[1].confidence < candidates[0].confidence);
+    }
+
+    #[test]
+    fn test_rib() {
+        let text = std::fs::read_to_string("tests/fixtures/rib/banque_postale_2.txt").unwrap();
+        let candidates =
+            classify_roles(extract_iban_candidates(&text, IbanSource::TextLayer), &text);
+
+        assert_eq!(candidates[0].role, IbanRole::AccountHolder);
+    }
+}
@@ -0,0 +1,218 @@
+use regex::Regex;
+
+// countries of the SEPA zone with the length and the bban format of their IBAN
+// https://www.europeanpaymentscouncil.eu/document-library/other/epc-list-sepa-scheme-countries
+// the format is the one of the swift iban registry, n: digit, a: letter, c: both
+pub const SEPA_COUNTRIES: &[(&str, usize, &str)] = &[
+    ("AD", 24, "4n4n12c"),
+    ("AL", 28, "8n16c"),
+    ("AT", 20, "5n11n"),
+    ("BE", 16, "3n7n2n"),
+    ("BG", 22, "4a4n2n8c"),
+    ("CH", 21, "5n12c"),
+    ("CY", 28, "3n5n16c"),
+    ("CZ", 24, "4n6n10n"),
+    ("DE", 22, "8n10n"),
+    ("DK", 18, "4n9n1n"),
+    ("EE", 20, "2n2n11n1n"),
+    ("ES", 24, "4n4n1n1n10n"),
+    ("FI", 18, "3n11n"),
+    ("FR", 27, "5n5n11c2n"),
+    ("GB", 22, "4a6n8n"),
+    ("GI", 23, "4a15c"),
+    ("GR", 27, "3n4n16c"),
+    ("HR", 21, "7n10n"),
+    ("HU", 28, "3n4n1n15n1n"),
+    ("IE", 22, "4a6n8n"),
+    ("IS", 26, "4n2n6n10n"),
+    ("IT", 27, "1a5n5n12c"),
+    ("LI", 21, "5n12c"),
+    ("LT", 20, "5n11n"),
+    ("LU", 20, "3n13c"),
+    ("LV", 21, "4a13c"),
+    ("MC", 27, "5n5n11c2n"),
+    ("MD", 24, "2c18c"),
+    ("ME", 22, "3n13n2n"),
+    ("MK", 19, "3n10c2n"),
+    ("MT", 31, "4a5n18c"),
+    ("NL", 18, "4a10n"),
+    ("NO", 15, "4n6n1n"),
+    ("PL", 28, "8n16n"),
+    ("PT", 25, "4n4n11n2n"),
+    ("RO", 24, "4a16c"),
+    ("SE", 24, "3n16n1n"),
+    ("SI", 19, "5n8n2n"),
+    ("SK", 24, "4n6n10n"),
+    ("SM", 27, "1a5n5n12c"),
+    ("VA", 22, "3n15n"),
+];
+
+pub fn is_sepa_country(country: &str) -> bool {
+    SEPA_COUNTRIES.iter().any(|(c, _, _)| *c == country)
+}
+
+// regex matching an IBAN of the country, tolerant to the separators
+// between the blocks of 4 characters.
+// the strict version follows the bban format, accepting in place of a digit
+// the letters the ocr confuses with digits, and the other way round.
+// the lax one only checks the length
+pub fn iban_pattern(country: &str, length: usize, bban: &str, lax: bool) -> String {
+    if lax {
+        return lax_iban_pattern(country, length);
+    }
+
+    let separator = r"[[[:space:]]\|,]*";
+    let mut pattern = format!(r"{}{}{{2}}", country, character_class('n'));
+
+    let types = bban_types(bban);
+    for block in types.chunks(4) {
+        pattern.push_str(&format!("({}", separator));
+        for run in block.chunk_by(|a, b| a == b) {
+            let class = character_class(run[0]);
+            if run.len() == 1 {
+                pattern.push_str(&class);
+            } else {
+                pattern.push_str(&format!("{}{{{}}}", class, run.len()));
+            }
+        }
+        pattern.push(')');
+    }
+
+    pattern
+}
+
+fn lax_iban_pattern(country: &str, length: usize) -> String {
+    let nb_blocks = (length - 4) / 4;
+    let remainder = (length - 4) % 4;
+    let separator = r"[[[:space:]]\|]*";
+
+    let mut pattern = format!(
+        r"{}[[:alnum:]]{{2}}({}[[:alnum:]]{{4}}){{{}}}",
+        country, separator, nb_blocks
+    );
+
+    if remainder > 0 {
+        pattern.push_str(&format!(r"({}[[:alnum:]]{{{}}})", separator, remainder));
+    }
+
+    pattern
+}
+
+// one type per character of the bban, ex: 2n1a gives n, n, a
+fn bban_types(bban: &str) -> Vec<char> {
+    let format = Regex::new(r"(\d+)([nac])").unwrap();
+
+    format
+        .captures_iter(bban)
+        .flat_map(|caps| {
+            let count = caps[1].parse::<usize>().unwrap();
+            let character_type = caps[2].chars().next().unwrap();
+            std::iter::repeat_n(character_type, count)
+        })
+        .collect()
+}
+
+fn character_class(character_type: char) -> String {
+    match character_type {
+        'n' => r"[[[:digit:]]ODQIlLSBZG]",
+        'a' => r"[[[:upper:]]012568]",
+        _ => r"[[:alnum:]]",
+    }
+    .to_string()
+}
+
+// regex matching the beginning of an IBAN of any SEPA country,
+// used to locate the iban in an image
+pub fn iban_anchor_pattern() -> String {
+    let countries = SEPA_COUNTRIES
+        .iter()
+        .map(|(country, _, _)| *country)
+        .collect::<Vec<&str>>()
+        .join("|");
+
+    format!(r"(?:^|\s)(?:{})[\dO]", countries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iban::Iban;
+
+    #[test]
+    fn test_iban_pattern() {
+        let digit = r"[[[:digit:]]ODQIlLSBZG]";
+        let block = format!(r"([[[:space:]]\|,]*{}{{4}})", digit);
+        assert_eq!(
+            iban_pattern("DE", 22, "8n10n", false),
+            format!(
+                r"DE{}{{2}}{}([[[:space:]]\|,]*{}{{2}})",
+                digit,
+                block.repeat(4),
+                digit
+            )
+        );
+        assert_eq!(
+            iban_pattern("NL", 18, "4a10n", false),
+            format!(
+                r"NL{}{{2}}([[[:space:]]\|,]*[[[:upper:]]012568]{{4}}){}([[[:space:]]\|,]*{}{{2}})",
+                digit,
+                block.repeat(2),
+                digit
+            )
+        );
+        assert_eq!(
+            iban_pattern("BE", 16, "3n7n2n", true),
+            r"BE[[:alnum:]]{2}([[[:space:]]\|]*[[:alnum:]]{4}){3}"
+        );
+    }
+
+    #[test]
+    fn test_bban_structure() {
+        let strict = |country: &str| {
+            let (_, length, bban) = SEPA_COUNTRIES
+                .iter()
+                .find(|(c, _, _)| *c == country)
+                .unwrap();
+            Regex::new(&format!(
+                "^{}$",
+                iban_pattern(country, *length, bban, false)
+            ))
+            .unwrap()
+        };
+
+        assert!(strict("DE").is_match("DE89 3704 0044 0532 0130 00"));
+        // a german bban only has digits
+        assert!(!strict("DE").is_match("DE89 3704 0044 0532 0130 AX"));
+        assert!(strict("NL").is_match("NL91 ABNA 0417 1643 00"));
+        assert!(!strict("NL").is_match("NL91 ABNA 0417 1643 AX"));
+        assert!(strict("FR").is_match("FR76 3000 1000 6449 1900 9562 088"));
+        // the letters confused with digits are left to the correction
+        assert!(strict("FR").is_match("FR76 3000 1000 6449 19OO 9562 088"));
+    }
+
+    #[test]
+    fn test_sepa_countries_length() {
+        let ibans = [
+            "DE89370400440532013000",
+            "BE68539007547034",
+            "ES9121000418450200051332",
+            "MC5811222000010123456789030",
+            "NL91ABNA0417164300",
+            "IT60X0542811101000000123456",
+            "PT50000201231234567890154",
+            "CH9300762011623852957",
+            "LU280019400644750000",
+            "AT611904300234573201",
+        ];
+
+        for iban in ibans {
+            assert!(iban.parse::<Iban>().is_ok(), "{}", iban);
+            let (_, length, bban) = SEPA_COUNTRIES
+                .iter()
+                .find(|(country, _, _)| *country == &iban[..2])
+                .unwrap();
+            assert_eq!(*length, iban.len(), "{}", iban);
+            assert_eq!(bban_types(bban).len() + 4, iban.len(), "{}", iban);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::{collections::HashSet, sync::LazyLock};
+
+// the built-in denylist, extended by the file set in LA_TAUPE_SPECIMENS
+const SPECIMENS_TXT: &str = include_str!("specimens.txt");
+static SPECIMENS: LazyLock<HashSet<String>> = LazyLock::new(|| {
+    let mut specimens = parse(SPECIMENS_TXT);
+
+    if let Some(path) = std::env::var("LA_TAUPE_SPECIMENS")
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+    {
+        match std::fs::read_to_string(&path) {
+            Ok(content) => specimens.extend(parse(&content)),
+            Err(e) => log::warn!("unable to read the specimens file {}: {}", path, e),
+        }
+    }
+
+    specimens
+});
+
+// one iban or bic per line, # starts a comment
+fn parse(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .map(normalize)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+pub fn is_specimen_iban(iban: &str) -> bool {
+    SPECIMENS.contains(&normalize(iban))
+}
+
+// a 0 as the second character of the location code is a swift test bic
+pub fn is_specimen_bic(bic: &str) -> bool {
+    let bic = normalize(bic);
+    SPECIMENS.contains(&bic) || bic.chars().nth(7) == Some('0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_specimen_iban() {
+        assert!(is_specimen_iban("FR76 3000 1000 6449 1900 9562 088"));
+        assert!(is_specimen_iban("de89370400440532013000"));
+        assert!(!is_specimen_iban("FR27 1820 6000 3060 0018 2530 127"));
+    }
+
+    #[test]
+    fn test_specimen_bic() {
+        assert!(is_specimen_bic("TESTFRP0"));
+        assert!(is_specimen_bic("BNPA FR P0 XXX"));
+        assert!(!is_specimen_bic("AGRIFRPP847"));
+        assert!(!is_specimen_bic("FTNOFRP1XXX"));
+    }
+
+    #[test]
+    fn test_parse() {
+        let specimens = parse("# comment\nFR27 1820 6000 3060 0018 2530 127 # test account\n\n");
+        assert_eq!(
+            specimens,
+            HashSet::from(["FR2718206000306000182530127".to_string()])
+        );
+    }
+}
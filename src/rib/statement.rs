@@ -0,0 +1,205 @@
+use std::sync::LazyLock;
+
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    text::date::{find_date, DATE_PATTERN},
+    twoddoc::cross_check::to_amount,
+};
+
+// a bank statement sent instead of a rib: the account identification
+// in the header, then pages of transactions
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Statement {
+    pub period: Option<Period>,
+    pub closing_balance: Option<Balance>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Period {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Balance {
+    // negative when the account is overdrawn
+    pub amount: f64,
+    pub date: Option<NaiveDate>,
+}
+
+static STATEMENT_TITLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(relev[ée] de (votre )?compte|extrait de compte|relev[ée] des op[ée]rations|relev[ée] mensuel|account statement|bank statement)").unwrap()
+});
+// the header of the transactions table, or its first line
+static TRANSACTIONS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(^\s*date\b.{0,40}\b(libell[ée]|op[ée]rations?|nature|d[ée]signation|description|d[ée]tail)\b|ancien solde|solde pr[ée]c[ée]dent)").unwrap()
+});
+static PERIOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)\b(du|from)\s+({})\s+(au|to)\s+({})",
+        DATE_PATTERN, DATE_PATTERN
+    ))
+    .unwrap()
+});
+static CLOSING_BALANCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(nouveau solde|solde final|solde de cl[ôo]ture|closing balance|solde (cr[ée]diteur |d[ée]biteur )?au)").unwrap()
+});
+static AMOUNT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"-?\s?\d{1,3}(?:[ \u{a0}.]\d{3})*,\d{2}\b|-?\d+\.\d{2}\b").unwrap()
+});
+// on a statement the holder is mostly labelled on its line
+static HOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?im)titulaire( du compte)?\s*:\s*(\S.*?)(\s{3,}|$)").unwrap());
+static DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!("(?i){}", DATE_PATTERN)).unwrap());
+static OVERDRAWN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)(d[ée]biteur|overdrawn)").unwrap());
+
+impl Statement {
+    pub fn detect(text: &str) -> bool {
+        STATEMENT_TITLE.is_match(text)
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let period = PERIOD.captures(text).and_then(|caps| {
+            Some(Period {
+                start: find_date(&caps[2])?,
+                end: find_date(&caps[4])?,
+            })
+        });
+
+        Statement {
+            period,
+            closing_balance: find_closing_balance(text),
+        }
+    }
+
+    // completed by the statement of the following pages,
+    // the closing balance is printed on the last one
+    pub fn followed_by(self, next: Statement) -> Self {
+        Statement {
+            period: self.period.or(next.period),
+            closing_balance: next.closing_balance.or(self.closing_balance),
+        }
+    }
+}
+
+// the header of each page, before its transactions, where the account
+// is identified. the transactions quote ibans and references of other accounts
+pub fn account_block(text: &str) -> String {
+    text.split('\x0c')
+        .map(|page| {
+            page.lines()
+                .take_while(|line| !TRANSACTIONS.is_match(line))
+                .collect::<Vec<&str>>()
+                .join("\n")
+        })
+        .filter(|header| !header.trim().is_empty())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn find_holder(text: &str) -> Option<Vec<String>> {
+    HOLDER
+        .captures(text)
+        .map(|caps| vec![caps[2].trim().to_string()])
+}
+
+// the last balance of the document, the opening one is printed first
+fn find_closing_balance(text: &str) -> Option<Balance> {
+    text.lines()
+        .rev()
+        .filter(|line| !TRANSACTIONS.is_match(line))
+        .find_map(|line| {
+            let m = CLOSING_BALANCE.find(line)?;
+            let rest = &line[m.end()..];
+            let date = find_date(rest);
+
+            // the amount is after the date, in the last column
+            let after_date = DATE.find(rest).map(|d| &rest[d.end()..]).unwrap_or(rest);
+            let amount = AMOUNT
+                .find_iter(after_date)
+                .filter_map(|a| to_amount(a.as_str()))
+                .last()?;
+
+            let sign = if amount < 0.0 || OVERDRAWN.is_match(line) {
+                -1.0
+            } else {
+                1.0
+            };
+
+            Some(Balance {
+                amount: sign * amount.abs(),
+                date,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = std::fs::read_to_string("tests/fixtures/rib/releve_de_compte.txt").unwrap();
+
+        assert!(Statement::detect(&text));
+        assert_eq!(
+            Statement::parse(&text),
+            Statement {
+                period: Some(Period {
+                    start: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                    end: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+                }),
+                closing_balance: Some(Balance {
+                    amount: 3243.6,
+                    date: NaiveDate::from_ymd_opt(2024, 3, 31),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_debit_balance() {
+        let text = "Relevé de compte du 1er avril 2024 au 30 avril 2024
+Solde débiteur au 30/04/2024      -120,50";
+
+        let statement = Statement::parse(text);
+        assert_eq!(statement.closing_balance.map(|b| b.amount), Some(-120.5));
+        assert_eq!(
+            statement.period.map(|p| p.start),
+            NaiveDate::from_ymd_opt(2024, 4, 1)
+        );
+    }
+
+    #[test]
+    fn test_followed_by() {
+        let first_page = Statement::parse(
+            "Relevé de compte du 1er avril 2024 au 30 avril 2024\nSolde au 01/04/2024      80,00",
+        );
+        let last_page = Statement::parse("Nouveau solde au 30/04/2024      -120,50");
+
+        let statement = first_page.followed_by(last_page);
+        assert_eq!(
+            statement.period.map(|p| p.end),
+            NaiveDate::from_ymd_opt(2024, 4, 30)
+        );
+        assert_eq!(statement.closing_balance.map(|b| b.amount), Some(-120.5));
+    }
+
+    #[test]
+    fn test_account_block() {
+        let text = std::fs::read_to_string("tests/fixtures/rib/releve_de_compte.txt").unwrap();
+        let block = account_block(&text);
+
+        assert!(block.contains("FR27 1820 6000 3060 0018 2530 127"));
+        assert!(!block.contains("FR05 1422"));
+        assert_eq!(
+            find_holder(&block),
+            Some(vec!["MLE FRIDA KAHLO".to_string()])
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    io::{Cursor, Write},
+    io::Cursor,
     process::{Command, Stdio},
 };
 
@@ -8,44 +8,118 @@ use itertools::Itertools;
 use regex::Regex;
 use scraper::{ElementRef, Html, Selector};
 
-use crate::shapes::{Anchor, Point};
+use crate::{
+    budget,
+    layout::Layout,
+    rib::sepa::iban_anchor_pattern,
+    shapes::{Anchor, Point},
+    tools::{require, tools},
+};
 
-pub fn img_to_string_using_tesseract(img: DynamicImage) -> String {
+pub fn img_to_string_using_tesseract(img: DynamicImage) -> Result<String, String> {
     let img = increase_image_size_if_needed(img);
+    let output = run_tesseract(&img, &[])?;
+
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+// the text of tesseract and the mean confidence of the words, between 0 and 1,
+// both written by the same run
+pub fn img_to_string_and_confidence_using_tesseract(
+    img: DynamicImage,
+) -> Result<(String, Option<f64>), String> {
+    let img = increase_image_size_if_needed(img);
+    let output = run_tesseract(&img, &["txt", "tsv"])?;
+    let (text, tsv) = split_output(&String::from_utf8_lossy(&output));
+
+    Ok((text, tsv_confidence(&tsv)))
+}
+
+fn run_tesseract(img: &DynamicImage, configs: &[&str]) -> Result<Vec<u8>, String> {
+    require("tesseract", &tools().tesseract)?;
 
     let mut buffer = Cursor::new(Vec::new());
-    img.write_to(&mut buffer, ImageFormat::Png).unwrap();
+    img.write_to(&mut buffer, ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode the image for tesseract: {}", e))?;
     let vec = buffer.into_inner();
 
-    let mut child = Command::new("tesseract")
-        .args([
-            "--psm",
-            "12",
-            "-c",
-            "preserve_interword_spaces=1",
-            "-l",
-            "fra",
-            "-",
-            "-",
-        ])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to start tesseract");
-
-    let mut stdin = child.stdin.take().expect("Failed to open stdin");
-    std::thread::spawn(move || {
-        stdin.write_all(&vec).expect("Failed to write to stdin");
-    });
-
-    let output = child.wait_with_output().expect("Failed to wait on child");
-
-    String::from_utf8_lossy(&output.stdout).to_string()
+    let mut args = vec![
+        "--psm",
+        "12",
+        "-c",
+        "preserve_interword_spaces=1",
+        "-l",
+        "fra",
+        "-",
+        "-",
+    ];
+    args.extend(configs);
+
+    budget::run(
+        Command::new("tesseract")
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped()),
+        vec,
+    )
+    .map_err(|e| format!("Failed to run tesseract: {}", e))
+}
+
+// the tsv and the text are written one after the other on stdout,
+// the tsv is its header and the rows that follow it
+fn split_output(output: &str) -> (String, String) {
+    let lines = output.split('\n').collect::<Vec<&str>>();
+    let Some(header) = lines
+        .iter()
+        .position(|line| line.starts_with("level\tpage_num"))
+    else {
+        return (output.to_string(), String::new());
+    };
+
+    let end = header
+        + 1
+        + lines[header + 1..]
+            .iter()
+            .take_while(|line| is_tsv_row(line))
+            .count();
+
+    let tsv = lines[header..end].join("\n");
+    let text = lines[..header].iter().chain(&lines[end..]).join("\n");
+
+    (text, tsv)
+}
+
+fn is_tsv_row(line: &str) -> bool {
+    let columns = line.split('\t').collect::<Vec<&str>>();
+    columns.len() == 12 && matches!(columns[0], "1" | "2" | "3" | "4" | "5")
+}
+
+// columns: level page_num block_num par_num line_num word_num left top width height conf text
+// only the words (level 5) have a confidence, -1 otherwise
+fn tsv_confidence(tsv: &str) -> Option<f64> {
+    let confidences = tsv
+        .lines()
+        .skip(1)
+        .map(|row| row.split('\t').collect::<Vec<&str>>())
+        .filter(|columns| {
+            columns.len() >= 12 && columns[0] == "5" && !columns[11].trim().is_empty()
+        })
+        .filter_map(|columns| columns[10].parse::<f64>().ok())
+        .filter(|conf| *conf >= 0.0)
+        .map(|conf| conf / 100.0)
+        .collect::<Vec<f64>>();
+
+    if confidences.is_empty() {
+        None
+    } else {
+        let mean = confidences.iter().sum::<f64>() / confidences.len() as f64;
+        Some((mean * 100.0).round() / 100.0)
+    }
 }
 
-pub fn tess_analyze(img: &DynamicImage) -> (String, Option<f32>, Option<Anchor>) {
-    let (hocr, doc) = image_to_hocr(img);
+// the words of the page, the angle of the text and the box of the iban
+pub fn tess_analyze(img: &DynamicImage) -> Result<(Layout, Option<f32>, Option<Anchor>), String> {
+    let (hocr, doc) = image_to_hocr(img)?;
     let (mut angle, mut anchor) = (None, None);
 
     if let Some(el) = iban_el(&doc) {
@@ -53,44 +127,17 @@ pub fn tess_analyze(img: &DynamicImage) -> (String, Option<f32>, Option<Anchor>)
         anchor = to_anchor(&el);
     };
 
-    (hocr, angle, anchor)
+    Ok((Layout::from_hocr(&hocr)?, angle, anchor))
 }
 
-fn image_to_hocr(img: &DynamicImage) -> (String, Html) {
-    let mut buffer = Cursor::new(Vec::new());
-    img.write_to(&mut buffer, ImageFormat::Png).unwrap();
-    let vec = buffer.into_inner();
+fn image_to_hocr(img: &DynamicImage) -> Result<(String, Html), String> {
+    let output = run_tesseract(img, &["hocr"])?;
 
-    let mut child = Command::new("tesseract")
-        .args([
-            "--psm",
-            "12",
-            "-c",
-            "preserve_interword_spaces=1",
-            "-l",
-            "fra",
-            "-",
-            "-",
-            "hocr",
-        ])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to start tesseract");
-
-    let mut stdin = child.stdin.take().expect("Failed to open stdin");
-    std::thread::spawn(move || {
-        stdin.write_all(&vec).expect("Failed to write to stdin");
-    });
-
-    let output = child.wait_with_output().expect("Failed to wait on child");
-
-    let hocr = String::from_utf8_lossy(&output.stdout).to_string();
+    let hocr = String::from_utf8_lossy(&output).to_string();
 
     let doc = Html::parse_document(&hocr);
 
-    (hocr, doc)
+    Ok((hocr, doc))
 }
 
 fn find_angle(hocr_string: &str, iban_anchor: ElementRef) -> Option<f32> {
@@ -135,9 +182,9 @@ fn find_angle(hocr_string: &str, iban_anchor: ElementRef) -> Option<f32> {
     angle
 }
 
-fn iban_el(doc: &Html) -> Option<ElementRef> {
+fn iban_el(doc: &Html) -> Option<ElementRef<'_>> {
     let selector = Selector::parse("span.ocrx_word").unwrap();
-    let re_iban = Regex::new(r"(?:^|\s)FR[\dO]").unwrap();
+    let re_iban = Regex::new(&iban_anchor_pattern()).unwrap();
 
     doc.select(&selector).find(|el| {
         let text = el.text().collect::<Vec<_>>().join("");
@@ -184,3 +231,33 @@ fn increase_image_size_if_needed(img: DynamicImage) -> DynamicImage {
         image::imageops::FilterType::Lanczos3,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_and_confidence() {
+        let output = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t800\t100\t-1\t
+4\t1\t1\t1\t1\t0\t10\t10\t700\t30\t-1\t
+5\t1\t1\t1\t1\t1\t10\t10\t60\t30\t96.5\tIBAN
+5\t1\t1\t1\t1\t2\t80\t10\t60\t30\t90\tFR76
+5\t1\t1\t1\t1\t3\t150\t10\t60\t30\t-1\t
+5\t1\t2\t1\t1\t1\t10\t60\t60\t30\t83.5\tBIC
+IBAN     FR76
+
+BIC
+";
+
+        // the text keeps the spaces and the blank lines of tesseract
+        let (text, tsv) = split_output(output);
+        assert_eq!(text, "IBAN     FR76\n\nBIC\n");
+        assert_eq!(tsv_confidence(&tsv), Some(0.9));
+
+        assert_eq!(
+            split_output("IBAN\n"),
+            ("IBAN\n".to_string(), String::new())
+        );
+    }
+}
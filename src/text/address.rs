@@ -1,4 +1,5 @@
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 use super::patch::Patch;
 
@@ -37,7 +38,8 @@ impl Addr {
     }
 }
 
-pub fn find_titulaire_addr(text: &str) -> Option<Addr> {
+// the blocks of text ending with a postal code and a city
+fn find_addrs(text: &str) -> Vec<Addr> {
     let lines: Vec<&str> = text.split("\n").collect();
     let code_postal = Regex::new(r"(^| )\d{5} ([[:alpha:]]+ ?)+").unwrap();
     let patch_upper_limit =
@@ -48,29 +50,40 @@ pub fn find_titulaire_addr(text: &str) -> Option<Addr> {
         .into_iter()
         .enumerate()
         .flat_map(|(index, line)| {
-            code_postal.find(line).map(|m| {
-                Patch::extract(
-                    &lines,
-                    index,
-                    &patch_upper_limit,
-                    m.start(),
-                    m.end() - 1,
-                    true,
-                    3,
-                )
-            })
+            // the holder and the bank branch may be printed side by side
+            code_postal
+                .find_iter(line)
+                .map(|m| {
+                    Patch::extract(
+                        &lines,
+                        index,
+                        &patch_upper_limit,
+                        m.start(),
+                        m.end() - 1,
+                        true,
+                        3,
+                    )
+                })
+                .collect::<Vec<Patch>>()
         });
 
-    let addresses = patches.map(|p| {
-        let addr_type = addr_type_from_text(&p);
-        Addr {
-            inner_lines: p.lines(),
-            addr_type,
-        }
-    });
+    patches
+        .map(|p| {
+            let addr_type = addr_type_from_text(&p);
+            Addr {
+                inner_lines: p.lines(),
+                addr_type,
+            }
+        })
+        .collect()
+}
+
+pub fn find_titulaire_addr(text: &str) -> Option<Addr> {
+    let addresses = find_addrs(text);
 
     let addr = addresses
         .clone()
+        .into_iter()
         .filter(|addr| addr.addr_type == AddrType::Titulaire)
         .collect::<Vec<Addr>>()
         .first()
@@ -80,6 +93,7 @@ pub fn find_titulaire_addr(text: &str) -> Option<Addr> {
         return Some(addr);
     }
     let addr = addresses
+        .into_iter()
         .filter(|addr| addr.addr_type == AddrType::Unknown)
         .collect::<Vec<Addr>>()
         .first()
@@ -88,6 +102,43 @@ pub fn find_titulaire_addr(text: &str) -> Option<Addr> {
     addr
 }
 
+pub fn find_domiciliation_addr(text: &str) -> Option<Addr> {
+    find_addrs(text)
+        .into_iter()
+        .find(|addr| addr.addr_type == AddrType::Domiciliation)
+}
+
+// an address split into its street lines, postal code and city
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PostalAddress {
+    pub lines: Vec<String>,
+    pub postal_code: Option<String>,
+    pub city: Option<String>,
+}
+
+impl PostalAddress {
+    pub fn parse(lines: &[String]) -> Self {
+        let code_postal = Regex::new(r"^(?:F-)?(\d{5})\s+(.+)$").unwrap();
+
+        let mut lines = lines.to_vec();
+        let (postal_code, city) = match lines.last().and_then(|l| code_postal.captures(l.trim())) {
+            Some(caps) => {
+                let postal_code = caps[1].to_string();
+                let city = caps[2].trim().to_string();
+                lines.pop();
+                (Some(postal_code), Some(city))
+            }
+            None => (None, None),
+        };
+
+        PostalAddress {
+            lines,
+            postal_code,
+            city,
+        }
+    }
+}
+
 fn addr_type_from_text(patch: &Patch) -> AddrType {
     let titulaire = Regex::new(r"(?i)(titulaire|intitulé)").unwrap();
     let domiciliation = Regex::new(r"(?i)(domiciliation|cadre réservé)").unwrap();
@@ -125,6 +176,41 @@ mod tests {
         assert_eq!(addrs.map(|a| a.lines()), titulaire);
     }
 
+    #[test]
+    fn test_postal_address() {
+        let lines = vec_to_string(vec!["51 RUE BERNARD ROY", "44100 NANTES"]);
+        assert_eq!(
+            PostalAddress::parse(&lines),
+            PostalAddress {
+                lines: vec_to_string(vec!["51 RUE BERNARD ROY"]),
+                postal_code: Some("44100".to_string()),
+                city: Some("NANTES".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_addresses_side_by_side() {
+        let text = "Titulaire                          Agence de domiciliation
+Mlle Frida Khalo                   TOURS HEURTELOUP
+117 rue des bourdonnieres          3 bd heurteloup
+44200 Nantes                       37000 Tours";
+
+        let addrs = find_addrs(text);
+        assert_eq!(addrs.len(), 2);
+        assert_eq!(addrs[0].addr_type, AddrType::Titulaire);
+        assert_eq!(addrs[1].addr_type, AddrType::Domiciliation);
+        assert_eq!(
+            find_domiciliation_addr(text).map(|a| a.lines()),
+            Some(vec_to_string(vec![
+                "Agence de domiciliation",
+                "TOURS HEURTELOUP",
+                "3 bd heurteloup",
+                "37000 Tours"
+            ]))
+        );
+    }
+
     #[test]
     fn addr_banque_populaire() {
         let path = "tests/fixtures/rib/banque_populaire.txt";
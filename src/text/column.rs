@@ -0,0 +1,52 @@
+// columns are separated by at least 3 spaces
+const COLUMN_GAP: &str = "   ";
+
+// the column, in characters, of the byte offset in its line
+pub fn column(text: &str, offset: usize) -> usize {
+    let line_start = text[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    text[line_start..offset].chars().count()
+}
+
+// the blocks of text of the line with their start and end columns
+pub fn segments(line: &str) -> Vec<(usize, usize, String)> {
+    let mut segments = vec![];
+    let mut col = 0;
+
+    for part in line.split(COLUMN_GAP) {
+        let trimmed = part.trim_start();
+        let start = col + part.chars().count() - trimmed.chars().count();
+        let text = trimmed.trim_end();
+
+        if !text.is_empty() {
+            segments.push((start, start + text.chars().count(), text.to_string()));
+        }
+
+        col += part.chars().count() + COLUMN_GAP.chars().count();
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments() {
+        assert_eq!(
+            segments("Titulaire :   M MATISSE HENRI  Paris   BIC"),
+            vec![
+                (0, 11, "Titulaire :".to_string()),
+                (14, 36, "M MATISSE HENRI  Paris".to_string()),
+                (39, 42, "BIC".to_string()),
+            ]
+        );
+        assert!(segments("   ").is_empty());
+    }
+
+    #[test]
+    fn test_column() {
+        let text = "IBAN\nTitulaire : é  HENRI";
+        assert_eq!(column(text, text.find("HENRI").unwrap()), 15);
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::LazyLock;
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+// a date written 12/03/2024, 12.03.2024, 12-03-2024 or 12 mars 2024
+pub const DATE_PATTERN: &str = r"\d{1,2}(?:er)?(?:[/.\-]\d{1,2}[/.\-]\d{4}| (?:janvier|f[ée]vrier|mars|avril|mai|juin|juillet|ao[ûu]t|septembre|octobre|novembre|d[ée]cembre) \d{4})";
+
+static NUMERIC_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d{1,2})[/.\-](\d{1,2})[/.\-](\d{4})").unwrap());
+static FRENCH_DATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(\d{1,2})(?:er)? (janvier|f[ée]vrier|mars|avril|mai|juin|juillet|ao[ûu]t|septembre|octobre|novembre|d[ée]cembre) (\d{4})").unwrap()
+});
+const MONTHS: [&str; 12] = [
+    "janvier",
+    "fevrier",
+    "mars",
+    "avril",
+    "mai",
+    "juin",
+    "juillet",
+    "aout",
+    "septembre",
+    "octobre",
+    "novembre",
+    "decembre",
+];
+
+// the first date, numeric or written in french
+pub fn find_date(s: &str) -> Option<NaiveDate> {
+    let numeric = NUMERIC_DATE.captures(s).and_then(|caps| {
+        NaiveDate::from_ymd_opt(
+            caps[3].parse().ok()?,
+            caps[2].parse().ok()?,
+            caps[1].parse().ok()?,
+        )
+        .map(|date| (caps.get(0).unwrap().start(), date))
+    });
+
+    let french = FRENCH_DATE.captures(s).and_then(|caps| {
+        let month = caps[2]
+            .to_lowercase()
+            .replace(['é', 'è'], "e")
+            .replace('û', "u");
+        let month = MONTHS.iter().position(|m| *m == month)? as u32 + 1;
+        NaiveDate::from_ymd_opt(caps[3].parse().ok()?, month, caps[1].parse().ok()?)
+            .map(|date| (caps.get(0).unwrap().start(), date))
+    });
+
+    [numeric, french]
+        .into_iter()
+        .flatten()
+        .min_by_key(|(start, _)| *start)
+        .map(|(_, date)| date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1);
+
+        assert_eq!(find_date("le 01/03/2024"), date);
+        assert_eq!(find_date("le 1er mars 2024, puis le 02/03/2024"), date);
+        assert_eq!(find_date("1 MARS 2024"), date);
+        assert_eq!(find_date("le 31/02/2024"), None);
+        assert!(Regex::new(DATE_PATTERN)
+            .unwrap()
+            .is_match("du 1er mars 2024"));
+    }
+}
@@ -0,0 +1,100 @@
+use strsim::normalized_levenshtein;
+
+// uppercase, without accents nor punctuation, words separated by one space
+pub fn normalize(s: &str) -> String {
+    s.chars()
+        .flat_map(|c| c.to_uppercase())
+        .flat_map(fold)
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn fold(c: char) -> Vec<char> {
+    match c {
+        'À' | 'Â' | 'Ä' | 'Á' | 'Ã' | 'Å' => vec!['A'],
+        'É' | 'È' | 'Ê' | 'Ë' => vec!['E'],
+        'Î' | 'Ï' | 'Í' | 'Ì' => vec!['I'],
+        'Ô' | 'Ö' | 'Ó' | 'Ò' | 'Õ' => vec!['O'],
+        'Ù' | 'Û' | 'Ü' | 'Ú' => vec!['U'],
+        'Ÿ' | 'Ý' => vec!['Y'],
+        'Ç' => vec!['C'],
+        'Ñ' => vec!['N'],
+        'Œ' => vec!['O', 'E'],
+        'Æ' => vec!['A', 'E'],
+        _ => vec![c],
+    }
+}
+
+// similarity between 0 and 1 of the normalized strings
+pub fn similarity(a: &str, b: &str) -> f32 {
+    normalized_levenshtein(&normalize(a), &normalize(b)) as f32
+}
+
+// finds the group of consecutive words of a line of the text which is the
+// most similar to the needle
+pub fn best_match(needle: &str, text: &str) -> Option<(String, f32)> {
+    let needle = normalize(needle);
+    let nb_words = needle.split(' ').count();
+
+    if needle.is_empty() {
+        return None;
+    }
+
+    text.lines()
+        .map(normalize)
+        .flat_map(|line| {
+            let words: Vec<String> = line.split(' ').map(|w| w.to_string()).collect();
+            let sizes = nb_words.saturating_sub(1).max(1)..=nb_words + 1;
+
+            sizes
+                .flat_map(|size| {
+                    words
+                        .windows(size.min(words.len()))
+                        .map(|window| window.join(" "))
+                        .collect::<Vec<String>>()
+                })
+                .collect::<Vec<String>>()
+        })
+        .filter(|candidate| !candidate.is_empty())
+        .map(|candidate| {
+            let score = normalized_levenshtein(&needle, &candidate) as f32;
+            (candidate, score)
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize("  Frédérique   d'Œillet "),
+            "FREDERIQUE D OEILLET"
+        );
+        assert_eq!(normalize("MLLE/SAMPLE/ANGELA"), "MLLE SAMPLE ANGELA");
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("Hélène", "HELENE"), 1.0);
+        assert!(similarity("MATISSE", "MATlSSE") > 0.8);
+        assert!(similarity("MATISSE", "KAHLO") < 0.5);
+    }
+
+    #[test]
+    fn test_best_match() {
+        let text = "Facture du 12/03/2024\n  Mlle Angela Sample     7 place des Spécimens\n";
+
+        let (found, score) = best_match("7 PLACE DES SPECIMENS", text).unwrap();
+        assert_eq!(found, "7 PLACE DES SPECIMENS");
+        assert_eq!(score, 1.0);
+
+        let (_, score) = best_match("8 RUE DU MOULIN", text).unwrap();
+        assert!(score < 0.6);
+    }
+}
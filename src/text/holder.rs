@@ -0,0 +1,335 @@
+use std::{collections::HashSet, sync::LazyLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{address::PostalAddress, fuzzy::normalize};
+
+// common french given names, uppercase without accents
+const PRENOMS_TXT: &str = include_str!("./prenoms.txt");
+static PRENOMS: LazyLock<HashSet<&str>> = LazyLock::new(|| PRENOMS_TXT.lines().collect());
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Person {
+    pub civilite: Option<String>,
+    pub surname: Option<String>,
+    pub given_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Company {
+    pub legal_form: String,
+    pub company_name: String,
+}
+
+// the account holders, as printed in the titulaire block
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Holder {
+    pub persons: Vec<Person>,
+    pub company: Option<Company>,
+    pub address: Option<PostalAddress>,
+}
+
+const CIVILITE: &str = r"(?i)^(m|monsieur|mr|mademoiselle|ml|mle|mlle|melle|madame|mme)\.?$";
+const LEGAL_FORM: &str =
+    r"(?i)^(sas|sasu|sarl|eurl|sa|sci|snc|scop|selarl|eirl|ei|gaec|earl|association|asso)$";
+
+impl Holder {
+    // the lines come from find_titulaire_addr, find_simple_titulaire
+    // or zoom_and_extract_titulaire: the names then the address
+    pub fn parse(lines: &[String]) -> Option<Self> {
+        // the iban may follow the holder in the block read
+        let lines = lines
+            .iter()
+            .filter(|line| !is_account_line(line))
+            .cloned()
+            .collect::<Vec<String>>();
+
+        let address_start = lines
+            .iter()
+            .position(|line| is_address_line(line))
+            .unwrap_or(lines.len());
+
+        let name = lines[..address_start].join(" ");
+        let address_lines = lines[address_start..].to_vec();
+
+        let address = if address_lines.is_empty() {
+            None
+        } else {
+            Some(PostalAddress::parse(&address_lines))
+        };
+
+        // a token with digits is never a name
+        let words = name
+            .split_whitespace()
+            .filter(|word| !word.chars().any(|c| c.is_ascii_digit()))
+            .collect::<Vec<&str>>();
+        if words.is_empty() {
+            return None;
+        }
+
+        let (persons, company) = match parse_company(&words) {
+            Some(company) => (vec![], Some(company)),
+            None => (parse_persons(&words), None),
+        };
+
+        Some(Holder {
+            persons,
+            company,
+            address,
+        })
+    }
+}
+
+// an iban, a bic after its label or a bare account number
+fn is_account_line(line: &str) -> bool {
+    let iban = Regex::new(r"(?i)^(iban\s*:?\s*)?[A-Z]{2}\d{2}( ?[A-Z0-9]){10,30}$").unwrap();
+    let bic = Regex::new(r"(?i)^(bic|swift)\b").unwrap();
+    let account_number = Regex::new(r"^[\d ]{8,}$").unwrap();
+
+    let line = line.trim();
+    iban.is_match(line) || bic.is_match(line) || account_number.is_match(line)
+}
+
+fn is_address_line(line: &str) -> bool {
+    let street = Regex::new(
+        r"(?i)^(\d|(rue|avenue|av|bd|boulevard|all[ée]e|chemin|place|route|impasse|quai|cours|lieu[- ]dit|bp|cs|r[ée]sidence|b[aâ]t)\b)",
+    )
+    .unwrap();
+
+    street.is_match(line.trim())
+}
+
+// the legal form is written before or after the company name
+fn parse_company(words: &[&str]) -> Option<Company> {
+    let legal_form = Regex::new(LEGAL_FORM).unwrap();
+
+    let (form, name) = if legal_form.is_match(words[0]) {
+        (words[0], &words[1..])
+    } else if legal_form.is_match(words[words.len() - 1]) {
+        (words[words.len() - 1], &words[..words.len() - 1])
+    } else {
+        return None;
+    };
+
+    Some(Company {
+        legal_form: form.to_uppercase(),
+        company_name: name.join(" "),
+    })
+}
+
+// co-holders are separated by OU, ET or &
+fn parse_persons(words: &[&str]) -> Vec<Person> {
+    let separator = Regex::new(r"(?i)^(ou|et|&|/)$").unwrap();
+
+    let mut persons = words
+        .split(|word| separator.is_match(word))
+        .filter(|part| !part.is_empty())
+        .map(parse_person)
+        .collect::<Vec<Person>>();
+
+    // "M OU MME MATISSE HENRI": the first holder shares the surname of the next
+    // and the given name, printed last, is his own
+    for i in (0..persons.len().saturating_sub(1)).rev() {
+        if persons[i].surname.is_none() {
+            persons[i].surname = persons[i + 1].surname.clone();
+            if persons[i].given_names.is_empty() {
+                persons[i].given_names = std::mem::take(&mut persons[i + 1].given_names);
+            }
+        }
+    }
+
+    persons
+}
+
+// banks mostly print the surname first, except when the surname
+// is the only word in capitals or the first word is a given name
+fn parse_person(words: &[&str]) -> Person {
+    let civilite = Regex::new(CIVILITE).unwrap();
+
+    let (civilite, names) = if civilite.is_match(words[0]) {
+        (
+            Some(words[0].trim_end_matches('.').to_uppercase()),
+            &words[1..],
+        )
+    } else {
+        (None, words)
+    };
+
+    let upper_words = names.iter().filter(|w| is_upper(w)).count();
+
+    let surname_index = if upper_words == 1 && names.len() > 1 {
+        names.iter().position(|w| is_upper(w)).unwrap()
+    } else if names.len() > 1 && is_prenom(names[0]) && !is_prenom(names[names.len() - 1]) {
+        names.len() - 1
+    } else {
+        0
+    };
+
+    let surname = names.get(surname_index).map(|s| s.to_string());
+    let given_names = names
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != surname_index)
+        .map(|(_, name)| name.to_string())
+        .collect();
+
+    Person {
+        civilite,
+        surname,
+        given_names,
+    }
+}
+
+fn is_prenom(word: &str) -> bool {
+    PRENOMS.contains(normalize(word).as_str())
+}
+
+fn is_upper(word: &str) -> bool {
+    word.chars().any(|c| c.is_alphabetic()) && word.to_uppercase() == word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::simple_titulaire::find_simple_titulaire;
+
+    fn vec_to_string(v: Vec<&str>) -> Vec<String> {
+        v.iter().map(|x| x.to_string()).collect()
+    }
+
+    fn person(civilite: Option<&str>, surname: &str, given_names: Vec<&str>) -> Person {
+        Person {
+            civilite: civilite.map(|c| c.to_string()),
+            surname: Some(surname.to_string()),
+            given_names: vec_to_string(given_names),
+        }
+    }
+
+    #[test]
+    fn test_co_holders() {
+        let lines = vec_to_string(vec![
+            "M OU MME MATISSE HENRI",
+            "51 RUE BERNARD ROY",
+            "44100 NANTES",
+        ]);
+        let holder = Holder::parse(&lines).unwrap();
+
+        assert_eq!(
+            holder.persons,
+            vec![
+                person(Some("M"), "MATISSE", vec!["HENRI"]),
+                person(Some("MME"), "MATISSE", vec![]),
+            ]
+        );
+        assert_eq!(holder.company, None);
+        assert_eq!(
+            holder.address,
+            Some(PostalAddress {
+                lines: vec_to_string(vec!["51 RUE BERNARD ROY"]),
+                postal_code: Some("44100".to_string()),
+                city: Some("NANTES".to_string()),
+            })
+        );
+
+        let lines = vec_to_string(vec!["Madame Khalo Frida ou Monsieur Matisse Henri"]);
+        let holder = Holder::parse(&lines).unwrap();
+        assert_eq!(
+            holder.persons,
+            vec![
+                person(Some("MADAME"), "Khalo", vec!["Frida"]),
+                person(Some("MONSIEUR"), "Matisse", vec!["Henri"]),
+            ]
+        );
+        assert_eq!(holder.address, None);
+    }
+
+    #[test]
+    fn test_account_lines() {
+        let text = "Titulaire du compte : MONSIEUR HENRI MATISSE
+FR27 1820 6000 3060 0018 2530 127";
+        let lines = find_simple_titulaire(text, 3).unwrap();
+        assert_eq!(
+            Holder::parse(&lines).unwrap().persons,
+            vec![person(Some("MONSIEUR"), "MATISSE", vec!["HENRI"])]
+        );
+
+        let text = "Titulaire du compte : MONSIEUR HENRI MATISSE
+IBAN FR27 1820 6000 3060 0018 2530 127
+BIC AGRIFRPP882";
+        let lines = find_simple_titulaire(text, 3).unwrap();
+        let holder = Holder::parse(&lines).unwrap();
+        assert_eq!(
+            holder.persons,
+            vec![person(Some("MONSIEUR"), "MATISSE", vec!["HENRI"])]
+        );
+        assert_eq!(holder.address, None);
+    }
+
+    #[test]
+    fn test_names_on_several_lines() {
+        let lines = vec_to_string(vec![
+            "MR OU MME MATISSE",
+            "HENRI",
+            "32 RUE EDOUARD TRAVIES",
+            "44240 LA CHAPELLE SUR ERDRE",
+        ]);
+        let holder = Holder::parse(&lines).unwrap();
+
+        assert_eq!(
+            holder.persons,
+            vec![
+                person(Some("MR"), "MATISSE", vec!["HENRI"]),
+                person(Some("MME"), "MATISSE", vec![]),
+            ]
+        );
+        assert_eq!(
+            holder.address.unwrap().city,
+            Some("LA CHAPELLE SUR ERDRE".to_string())
+        );
+    }
+
+    #[test]
+    fn test_surname_in_capitals() {
+        let lines = vec_to_string(vec!["Mme Frida KAHLO"]);
+        let holder = Holder::parse(&lines).unwrap();
+
+        assert_eq!(
+            holder.persons,
+            vec![person(Some("MME"), "KAHLO", vec!["Frida"])]
+        );
+    }
+
+    #[test]
+    fn test_given_name_first() {
+        let lines = vec_to_string(vec!["M HENRI MATISSE OU MLLE", "FRIDA KAHLO"]);
+        let holder = Holder::parse(&lines).unwrap();
+
+        assert_eq!(
+            holder.persons,
+            vec![
+                person(Some("M"), "MATISSE", vec!["HENRI"]),
+                person(Some("MLLE"), "KAHLO", vec!["FRIDA"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_company() {
+        let lines = vec_to_string(vec![
+            "SAS HENRI MATISSE",
+            "18 RUE SADI CARNOT",
+            "92120 MONTROUGE",
+        ]);
+        let holder = Holder::parse(&lines).unwrap();
+
+        assert_eq!(holder.persons, vec![]);
+        assert_eq!(
+            holder.company,
+            Some(Company {
+                legal_form: "SAS".to_string(),
+                company_name: "HENRI MATISSE".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use strsim::normalized_damerau_levenshtein;
+
+use super::{
+    fuzzy::normalize,
+    holder::{Holder, Person},
+};
+
+const MATCH_THRESHOLD: f64 = 0.85;
+// an initial, as in "M MATISSE H", is less certain than the full word
+const INITIAL_SCORE: f64 = 0.9;
+
+const NOISE: &str = r"^(M|MR|MME|MLLE|MLE|ML|MELLE|MONSIEUR|MADAME|MADEMOISELLE|SAS|SASU|SARL|EURL|SA|SCI|SNC|SCOP|SELARL|EIRL|EI|GAEC|EARL|ASSOCIATION|ASSO|OU|ET|DE|DU|LA|LE)$";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Match,
+    Mismatch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HolderMatch {
+    pub expected: String,
+    // the holder of the rib the closest to the expected name
+    pub matched: Option<String>,
+    pub score: f64,
+    pub verdict: Verdict,
+}
+
+// compares the expected name, of a person or a company,
+// with each holder of the account
+pub fn match_holder(expected: &str, holder: Option<&Holder>, titulaire: &[String]) -> HolderMatch {
+    let expected_words = words(expected);
+
+    let mut candidates: Vec<Vec<String>> = vec![];
+
+    if let Some(holder) = holder {
+        candidates.extend(holder.persons.iter().map(person_words));
+        if let Some(company) = &holder.company {
+            candidates.push(words(&company.company_name));
+        }
+    }
+
+    // without structured holder, the raw lines
+    if candidates.is_empty() {
+        candidates.extend(titulaire.iter().map(|line| words(line)));
+    }
+
+    let best = candidates
+        .into_iter()
+        .filter(|candidate| !candidate.is_empty())
+        .map(|candidate| {
+            let score = score(&expected_words, &candidate);
+            (candidate.join(" "), score)
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    let (matched, score) = match best {
+        Some((matched, score)) => (Some(matched), (score * 100.0).round() / 100.0),
+        None => (None, 0.0),
+    };
+
+    let verdict = if score >= MATCH_THRESHOLD {
+        Verdict::Match
+    } else {
+        Verdict::Mismatch
+    };
+
+    HolderMatch {
+        expected: expected.to_string(),
+        matched,
+        score,
+        verdict,
+    }
+}
+
+// normalized words, without civilité, legal form nor linking words
+fn words(s: &str) -> Vec<String> {
+    let noise = Regex::new(NOISE).unwrap();
+
+    normalize(s)
+        .split(' ')
+        .filter(|w| !w.is_empty() && !noise.is_match(w))
+        .map(|w| w.to_string())
+        .collect()
+}
+
+fn person_words(person: &Person) -> Vec<String> {
+    person
+        .surname
+        .iter()
+        .chain(person.given_names.iter())
+        .flat_map(|name| words(name))
+        .collect()
+}
+
+// each expected word is paired with a distinct word of the candidate, the closest
+// pairs first, whatever the order: the surname and the given names can be reversed
+fn score(expected: &[String], candidate: &[String]) -> f64 {
+    if expected.is_empty() {
+        return 0.0;
+    }
+
+    let mut pairs = expected
+        .iter()
+        .enumerate()
+        .flat_map(|(i, e)| {
+            candidate
+                .iter()
+                .enumerate()
+                .map(move |(j, c)| (word_score(e, c), i, j))
+        })
+        .collect::<Vec<(f64, usize, usize)>>();
+    pairs.sort_by(|(a, _, _), (b, _, _)| b.total_cmp(a));
+
+    let mut expected_used = vec![false; expected.len()];
+    let mut candidate_used = vec![false; candidate.len()];
+    let mut total = 0.0;
+
+    for (score, i, j) in pairs {
+        if expected_used[i] || candidate_used[j] {
+            continue;
+        }
+        expected_used[i] = true;
+        candidate_used[j] = true;
+        total += score;
+    }
+
+    total / expected.len() as f64
+}
+
+fn word_score(expected: &str, found: &str) -> f64 {
+    if found.len() == 1 || expected.len() == 1 {
+        return if expected.starts_with(found) || found.starts_with(expected) {
+            INITIAL_SCORE
+        } else {
+            0.0
+        };
+    }
+
+    // damerau: a transposition of two letters is a single edit
+    normalized_damerau_levenshtein(expected, found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holder_match(expected: &str, lines: Vec<&str>) -> HolderMatch {
+        let lines: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        let holder = Holder::parse(&lines);
+        match_holder(expected, holder.as_ref(), &lines)
+    }
+
+    #[test]
+    fn test_match_person() {
+        let result = holder_match(
+            "Henri Matisse",
+            vec!["M MATISSE HENRI", "51 RUE BERNARD ROY"],
+        );
+        assert_eq!(result.verdict, Verdict::Match);
+        assert_eq!(result.score, 1.0);
+        assert_eq!(result.matched, Some("MATISSE HENRI".to_string()));
+
+        let result = holder_match("Frédérique Dupré", vec!["MME DUPRE FREDERIQUE"]);
+        assert_eq!(result.verdict, Verdict::Match);
+
+        let result = holder_match("Henri Matisse", vec!["ML KHALO FRIDA OU M MATISSE H"]);
+        assert_eq!(result.verdict, Verdict::Match);
+        assert_eq!(result.matched, Some("MATISSE H".to_string()));
+
+        let result = holder_match("Frida Kahlo", vec!["ML KHALO FRIDA OU M MATISSE H"]);
+        assert_eq!(result.verdict, Verdict::Match);
+        assert_eq!(result.score, 0.9);
+
+        let result = holder_match("Pablo Picasso", vec!["M OU MME MATISSE HENRI"]);
+        assert_eq!(result.verdict, Verdict::Mismatch);
+
+        // a word of the holder is only used once
+        let result = holder_match("Matisse Matisse", vec!["M MATISSE HENRI"]);
+        assert_eq!(result.verdict, Verdict::Mismatch);
+    }
+
+    #[test]
+    fn test_match_company() {
+        let result = holder_match("Henri Matisse SAS", vec!["SAS HENRI MATISSE"]);
+        assert_eq!(result.verdict, Verdict::Match);
+        assert_eq!(result.score, 1.0);
+    }
+}
@@ -1,3 +1,8 @@
 pub mod address;
+pub mod column;
+pub mod date;
+pub mod fuzzy;
+pub mod holder;
+pub mod holder_match;
 pub mod patch;
 pub mod simple_titulaire;
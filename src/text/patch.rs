@@ -67,6 +67,15 @@ impl Patch {
             .filter(|line| !line.is_empty())
             .collect()
     }
+
+    // each line of the patch with the text on its left
+    pub fn lines_with_context(&self) -> Vec<(String, String)> {
+        self.inner_lines
+            .iter()
+            .cloned()
+            .zip(self.context_lines.iter().cloned())
+            .collect()
+    }
 }
 
 fn complete(line: &str, start: usize, end: usize) -> Option<(String, String, usize, usize)> {
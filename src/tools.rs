@@ -0,0 +1,95 @@
+use std::{process::Command, sync::LazyLock};
+
+use serde::Serialize;
+
+// the versions of the external programs, None when they are not installed.
+// detected once, at startup for the server
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Tools {
+    pub pdftotext: Option<String>,
+    pub pdftoppm: Option<String>,
+    pub pdfimages: Option<String>,
+    pub tesseract: Option<String>,
+}
+
+static TOOLS: LazyLock<Tools> = LazyLock::new(Tools::detect);
+
+pub fn tools() -> &'static Tools {
+    &TOOLS
+}
+
+impl Tools {
+    pub fn detect() -> Self {
+        Tools {
+            pdftotext: version("pdftotext", "-v"),
+            pdftoppm: version("pdftoppm", "-v"),
+            pdfimages: version("pdfimages", "-v"),
+            tesseract: version("tesseract", "--version"),
+        }
+    }
+
+    pub fn log(&self) {
+        [
+            ("pdftotext", &self.pdftotext),
+            ("pdftoppm", &self.pdftoppm),
+            ("pdfimages", &self.pdfimages),
+            ("tesseract", &self.tesseract),
+        ]
+        .iter()
+        .for_each(|(name, version)| match version {
+            Some(version) => log::info!("{}: {}", name, version),
+            None => log::warn!("{} is not installed", name),
+        });
+
+        if self.pdftotext.is_none() || self.pdftoppm.is_none() {
+            log::warn!("pdf files cannot be analyzed without poppler-utils");
+        }
+        if self.tesseract.is_none() {
+            log::warn!("the ocr runs with ocrs only");
+        }
+    }
+}
+
+// the error of a request that needs a missing program
+pub fn require(name: &str, version: &Option<String>) -> Result<(), String> {
+    match version {
+        Some(_) => Ok(()),
+        None => Err(format!("{} is not installed on the server", name)),
+    }
+}
+
+// the first line printed, poppler prints its version on stderr
+fn version(program: &str, arg: &str) -> Option<String> {
+    let output = Command::new(program).arg(arg).output().ok()?;
+
+    [output.stdout, output.stderr]
+        .iter()
+        .flat_map(|out| {
+            String::from_utf8_lossy(out)
+                .lines()
+                .map(|line| line.trim().to_string())
+                .collect::<Vec<String>>()
+        })
+        .find(|line| !line.is_empty())
+        .or(Some("unknown".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version() {
+        assert_eq!(version("la_taupe_missing_tool", "-v"), None);
+        assert!(version("cat", "--version").is_some());
+    }
+
+    #[test]
+    fn test_require() {
+        assert!(require("pdftotext", &Some("pdftotext version 24.02.0".to_string())).is_ok());
+        assert_eq!(
+            require("pdftotext", &None),
+            Err("pdftotext is not installed on the server".to_string())
+        );
+    }
+}
@@ -5,21 +5,35 @@ use x509_cert::{
     Certificate,
 };
 
-use crate::twoddoc::trust_service::trust_service;
+use crate::twoddoc::trust_service::find_trust_service;
 
 static TEST_CERTIFICATE: &[u8] =
     include_bytes!("../../tests/fixtures/certificates/certificate_FR00_00.pem");
 
 pub fn certificate(autorite_certification: &str, identifiant_du_certificat: &str) -> Certificate {
+    try_certificate(autorite_certification, identifiant_du_certificat).unwrap()
+}
+
+pub fn try_certificate(
+    autorite_certification: &str,
+    identifiant_du_certificat: &str,
+) -> Result<Certificate, String> {
     if autorite_certification == "FR00" {
-        Certificate::from_pem(TEST_CERTIFICATE).unwrap()
+        Certificate::from_pem(TEST_CERTIFICATE)
+            .map_err(|e| format!("Failed to parse test certificate: {}", e))
     } else {
         fetch_certificate(autorite_certification, identifiant_du_certificat)
     }
 }
 
-fn fetch_certificate(autorite_certification: &str, identifiant_du_certificat: &str) -> Certificate {
-    let service = trust_service(autorite_certification);
+fn fetch_certificate(
+    autorite_certification: &str,
+    identifiant_du_certificat: &str,
+) -> Result<Certificate, String> {
+    let service = find_trust_service(autorite_certification).ok_or(format!(
+        "Unknown certification authority: {}",
+        autorite_certification
+    ))?;
 
     let mut url: Url = service.information_url;
     url.query_pairs_mut()
@@ -28,12 +42,22 @@ fn fetch_certificate(autorite_certification: &str, identifiant_du_certificat: &s
     log::trace!("Fetching certificate from {}", url);
 
     let client = Client::new();
-    let resp = client.get(url.as_str()).send().unwrap();
+    let resp = client
+        .get(url.as_str())
+        .send()
+        .map_err(|e| format!("Failed to fetch certificate: {}", e))?;
 
     if !resp.status().is_success() {
-        panic!("Failed to fetch certificate: HTTP {}", resp.status());
+        return Err(format!(
+            "Failed to fetch certificate: HTTP {}",
+            resp.status()
+        ));
     }
 
-    let bytes = resp.bytes().unwrap().to_vec();
-    Certificate::from_der(&bytes[..]).unwrap()
+    let bytes = resp
+        .bytes()
+        .map_err(|e| format!("Failed to fetch certificate: {}", e))?
+        .to_vec();
+
+    Certificate::from_der(&bytes[..]).map_err(|e| format!("Failed to parse certificate: {}", e))
 }
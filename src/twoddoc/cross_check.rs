@@ -0,0 +1,280 @@
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{data_structure::data_structure_from_json, ddoc::Ddoc, utils::to_date};
+use crate::text::fuzzy::{best_match, normalize};
+
+// above this score, a field is considered as printed on the document
+const MATCH_THRESHOLD: f32 = 0.8;
+
+const NAME_IDS: &[&str] = &[
+    "10", "12", "13", "14", "16", "17", "1I", "1K", "1L", "1M", "1O", "1P", "30", "46", "48", "4N",
+    "4U", "5M", "61", "62", "63", "80", "81",
+];
+const ADDRESS_IDS: &[&str] = &[
+    "20", "21", "22", "23", "25", "27", "28", "29", "2A", "2C", "4O", "4Q", "5D", "5E", "5F", "5G",
+    "5I", "6S", "6T", "6U", "6V", "6X",
+];
+const POSTAL_CODE_IDS: &[&str] = &["24", "2B", "4P", "5H", "6W"];
+const AMOUNT_IDS: &[&str] = &[
+    "1D", "41", "4D", "4E", "4I", "4J", "58", "59", "5A", "5B", "5C", "G4",
+];
+// dates encoded as a number of days since 2000-01-01 in hexadecimal
+const HEX_DATE_IDS: &[&str] = &["06", "08", "36", "53", "54", "56"];
+// dates encoded as DDMMYYYY
+const DATE_IDS: &[&str] = &["1C", "4A", "4B", "4C", "55", "57", "69", "6N", "6O"];
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    Name,
+    Address,
+    PostalCode,
+    Amount,
+    Date,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Match,
+    Mismatch,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FieldCheck {
+    pub id: String,
+    pub nom: String,
+    pub kind: FieldKind,
+    pub expected: String,
+    pub found: Option<String>,
+    pub score: f32,
+    pub verdict: Verdict,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CrossCheck {
+    pub fields: Vec<FieldCheck>,
+}
+
+impl CrossCheck {
+    pub fn mismatches(&self) -> Vec<&FieldCheck> {
+        self.fields
+            .iter()
+            .filter(|f| f.verdict == Verdict::Mismatch)
+            .collect()
+    }
+}
+
+// compares the signed data of the 2D-Doc with the text printed on the document
+pub fn cross_check(ddoc: &Ddoc, text: &str) -> CrossCheck {
+    let structures = data_structure_from_json();
+
+    let mut ids: Vec<&String> = ddoc.data.keys().collect();
+    ids.sort();
+
+    let fields = ids
+        .into_iter()
+        .filter_map(|id| {
+            let value = ddoc.data.get(id)?;
+            let kind = field_kind(id)?;
+
+            if value.trim().is_empty() {
+                return None;
+            }
+
+            let (found, score) = match kind {
+                FieldKind::Name => find_name(value, text),
+                FieldKind::Address | FieldKind::PostalCode => find_text(value, text),
+                FieldKind::Amount => find_amount(value, text),
+                FieldKind::Date => find_date(id, value, text),
+            }
+            .map_or((None, 0.0), |(found, score)| (Some(found), score));
+
+            let nom = structures
+                .iter()
+                .find(|s| &s.id == id)
+                .map(|s| s.nom.clone())
+                .unwrap_or_default();
+
+            Some(FieldCheck {
+                id: id.clone(),
+                nom,
+                kind,
+                expected: value.clone(),
+                found,
+                score,
+                verdict: if score >= MATCH_THRESHOLD {
+                    Verdict::Match
+                } else {
+                    Verdict::Mismatch
+                },
+            })
+        })
+        .collect();
+
+    CrossCheck { fields }
+}
+
+fn field_kind(id: &str) -> Option<FieldKind> {
+    if NAME_IDS.contains(&id) {
+        Some(FieldKind::Name)
+    } else if ADDRESS_IDS.contains(&id) {
+        Some(FieldKind::Address)
+    } else if POSTAL_CODE_IDS.contains(&id) {
+        Some(FieldKind::PostalCode)
+    } else if AMOUNT_IDS.contains(&id) {
+        Some(FieldKind::Amount)
+    } else if HEX_DATE_IDS.contains(&id) || DATE_IDS.contains(&id) {
+        Some(FieldKind::Date)
+    } else {
+        None
+    }
+}
+
+fn find_text(value: &str, text: &str) -> Option<(String, f32)> {
+    best_match(value, text)
+}
+
+// names are encoded as "MLLE/SAMPLE/ANGELA" but can be printed
+// in any order, so we also try the reversed order
+fn find_name(value: &str, text: &str) -> Option<(String, f32)> {
+    let words: Vec<&str> = value.split(['/', ' ']).filter(|w| !w.is_empty()).collect();
+
+    let straight = words.join(" ");
+    let reversed = words.iter().rev().cloned().collect::<Vec<&str>>().join(" ");
+
+    let civilite = Regex::new(r"^(M|MR|MME|MLLE|MLE|MELLE|MONSIEUR|MADAME)$").unwrap();
+    let without_civilite = words
+        .iter()
+        .filter(|w| !civilite.is_match(&normalize(w)))
+        .cloned()
+        .collect::<Vec<&str>>();
+    let reversed_without_civilite = without_civilite
+        .iter()
+        .rev()
+        .cloned()
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    [
+        straight,
+        reversed,
+        without_civilite.join(" "),
+        reversed_without_civilite,
+    ]
+    .iter()
+    .filter_map(|candidate| best_match(candidate, text))
+    .max_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+pub(crate) fn to_amount(s: &str) -> Option<f64> {
+    let cleaned: String = s
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\u{a0}' && *c != '€')
+        .collect();
+
+    // 1.234,56 or 1 234,56 or 1234.56
+    let cleaned = if cleaned.contains(',') {
+        cleaned.replace('.', "").replace(',', ".")
+    } else {
+        cleaned
+    };
+
+    cleaned.parse::<f64>().ok()
+}
+
+fn find_amount(value: &str, text: &str) -> Option<(String, f32)> {
+    let expected = to_amount(value)?;
+    let amount_re = Regex::new(r"\d+(?:[ \u{a0}.]\d{3})*(?:[.,]\d{1,2})?").unwrap();
+
+    let found = amount_re
+        .find_iter(text)
+        .map(|m| m.as_str().to_string())
+        .find(|found| to_amount(found).is_some_and(|a| (a - expected).abs() < 0.005));
+
+    found.map(|found| (found, 1.0))
+}
+
+fn find_date(id: &str, value: &str, text: &str) -> Option<(String, f32)> {
+    let date = if HEX_DATE_IDS.contains(&id) {
+        to_date(value)?.date()
+    } else {
+        NaiveDate::parse_from_str(value, "%d%m%Y").ok()?
+    };
+
+    let date_re = Regex::new(r"(\d{1,2})[/.\- ](\d{1,2})[/.\- ](\d{4}|\d{2})").unwrap();
+
+    let found = date_re
+        .captures_iter(text)
+        .find(|caps| {
+            let day = caps[1].parse::<u32>().ok();
+            let month = caps[2].parse::<u32>().ok();
+            let year =
+                caps[3]
+                    .parse::<i32>()
+                    .ok()
+                    .map(|y| if caps[3].len() == 2 { 2000 + y } else { y });
+
+            match (year, month, day) {
+                (Some(y), Some(m), Some(d)) => NaiveDate::from_ymd_opt(y, m, d) == Some(date),
+                _ => false,
+            }
+        })
+        .map(|caps| caps[0].to_string());
+
+    found.map(|found| (found, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twoddoc::parse;
+
+    fn ddoc() -> Ddoc {
+        let i = "DC04FR000001FFFF1FB60401FR432,7544227801234567845202146RETI PATRICK4A31072022416319847300112345678948RETI SOPHIE490701987765432QHA4A6QOV6AZJEBTIUNR7QOBXINNTMZTD5COQH6VN24NCZTXA7MYXB6SNSNTWAQRYK3ZFP4ZWBGLTJ6SDSPMURF7YFILKQFIAJY7NTI";
+        parse(i).unwrap()
+    }
+
+    fn verdict(cross_check: &CrossCheck, id: &str) -> Verdict {
+        cross_check
+            .fields
+            .iter()
+            .find(|f| f.id == id)
+            .unwrap()
+            .verdict
+    }
+
+    #[test]
+    fn test_cross_check() {
+        let text = "
+            AVIS D'IMPÔT 2022 SUR LES REVENUS DE L'ANNÉE 2021
+            Déclarant 1 : Patrick Reti          Déclarant 2 : Sophie RETI
+            Date de mise en recouvrement : 31/07/2022
+            Revenu fiscal de référence : 63 198 €
+        ";
+
+        let cross_check = cross_check(&ddoc(), text);
+
+        assert_eq!(verdict(&cross_check, "46"), Verdict::Match);
+        assert_eq!(verdict(&cross_check, "48"), Verdict::Match);
+        assert_eq!(verdict(&cross_check, "4A"), Verdict::Match);
+        assert_eq!(verdict(&cross_check, "41"), Verdict::Match);
+        assert!(cross_check.mismatches().is_empty());
+    }
+
+    #[test]
+    fn test_cross_check_altered_document() {
+        let text = "
+            Déclarant 1 : Patrick Reti          Déclarant 2 : Sophie RETI
+            Date de mise en recouvrement : 31/07/2022
+            Revenu fiscal de référence : 23 198 €
+        ";
+
+        let cross_check = cross_check(&ddoc(), text);
+
+        assert_eq!(verdict(&cross_check, "41"), Verdict::Mismatch);
+        assert_eq!(cross_check.mismatches().len(), 1);
+    }
+}
@@ -1,4 +1,7 @@
-use super::entete::Entete;
+use super::{
+    entete::Entete,
+    registry::{Fingerprint, Sighting},
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -6,10 +9,28 @@ use std::collections::HashMap;
 pub struct Ddoc {
     pub entete: Entete,
     pub data: HashMap<String, String>,
+    #[serde(skip)]
+    pub signature: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub already_seen: Option<Sighting>,
 }
 
 impl Ddoc {
-    pub fn new(entete: Entete, data: HashMap<String, String>) -> Self {
-        Ddoc { entete, data }
+    pub fn new(entete: Entete, data: HashMap<String, String>, signature: String) -> Self {
+        Ddoc {
+            entete,
+            data,
+            signature,
+            already_seen: None,
+        }
+    }
+
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint {
+            autorite_certification: self.entete.autorite_certification.clone(),
+            identifiant_du_certificat: self.entete.identifiant_du_certificat.clone(),
+            signature: self.signature.clone(),
+            document_id: self.data.get("01").cloned(),
+        }
     }
 }
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use self::{
     ddoc::Ddoc,
     entete::Entete,
+    signature::Verification,
     utils::{date, date_option, four_alphanum, two_alphanum, two_digit},
 };
 
@@ -18,14 +19,29 @@ use nom::{
 };
 
 mod certificate_store;
+pub mod cross_check;
 pub mod data_structure;
 pub mod ddoc;
 pub mod entete;
-mod signature;
+pub mod registry;
+pub mod signature;
 pub mod trust_service;
 pub mod utils;
 
 pub fn parse(doc: &str) -> Option<Ddoc> {
+    let ddoc = decode(doc)?;
+
+    check_signature(
+        doc,
+        &ddoc.entete.autorite_certification,
+        &ddoc.entete.identifiant_du_certificat,
+    );
+
+    Some(ddoc)
+}
+
+// parses the 2D-Doc without checking its signature
+pub fn decode(doc: &str) -> Option<Ddoc> {
     let (i, version) = version(doc)?;
 
     let (message, entete) = match version {
@@ -84,13 +100,23 @@ pub fn parse(doc: &str) -> Option<Ddoc> {
         .map(|(k, v)| (k.to_string(), v.to_string()))
         .collect();
 
-    check_signature(
-        doc,
-        &entete.autorite_certification,
-        &entete.identifiant_du_certificat,
-    );
+    let (_, signature) = split_signature(doc)?;
 
-    Some(Ddoc::new(entete, bag))
+    Some(Ddoc::new(entete, bag, signature.to_string()))
+}
+
+// details the verification of the signature and of the certificate chain
+pub fn verify(doc: &str) -> Option<Verification> {
+    let ddoc = decode(doc)?;
+    let (payload, signature) = split_signature(doc)?;
+
+    Some(signature::verify(
+        payload,
+        signature,
+        &ddoc.entete.autorite_certification,
+        &ddoc.entete.identifiant_du_certificat,
+        ddoc.entete.date_creation_signature,
+    ))
 }
 
 pub fn version(i: &str) -> Option<(&str, u32)> {
@@ -108,14 +134,19 @@ fn datum(i: &str) -> IResult<&str, (&str, &str)> {
     .map(|(i, data)| (i, (data_id, data)))
 }
 
-fn check_signature(i: &str, autorite_certification: &str, identifiant_du_certificat: &str) {
-    let (_, (payload, signature)) = separated_pair(
+fn split_signature(i: &str) -> Option<(&str, &str)> {
+    separated_pair(
         is_not::<&str, &str, Error<&str>>(""),
         tag(""),
         alphanumeric1,
     )
     .parse(i)
-    .unwrap();
+    .ok()
+    .map(|(_, payload_and_signature)| payload_and_signature)
+}
+
+fn check_signature(i: &str, autorite_certification: &str, identifiant_du_certificat: &str) {
+    let (payload, signature) = split_signature(i).unwrap();
 
     signature::check(
         payload,
@@ -249,4 +280,24 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn test_verify_v2_doc_00() {
+        let i = "DC02FR000001125E125C0026FR245700010MLLE/SAMPLE/ANGELA2021BAT 2 ETG 32325METZ227 PLACE DES SPECIMENSZ2HSK7UZM6KPL7UL6OK7NR77GSPGPNNUYYEE4ZV75L5OCIWKVOXTV3I5AJLRSUDOIR76F75QY5Z7KLH3FACKHVF7JH3DYMRI5EIAZMI";
+
+        let verification = verify(i).unwrap();
+
+        assert!(verification.signature_valid);
+        assert_eq!(verification.certificate_valid_at_signature, Some(true));
+        // FR00 is the test authority, it is not in the trust service list
+        assert_eq!(verification.chain_valid, None);
+        assert_eq!(
+            verification.errors,
+            vec!["FR00 is not in the trust service list".to_string()]
+        );
+
+        let altered = i.replace("METZ", "NICE");
+        let verification = verify(&altered).unwrap();
+        assert!(!verification.signature_valid);
+    }
 }
@@ -0,0 +1,140 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::Mutex,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// the registry is a json lines file, one entry per line.
+// all the workers of the server share the same file, so we serialize the accesses
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Fingerprint {
+    pub autorite_certification: String,
+    pub identifiant_du_certificat: String,
+    pub signature: String,
+    pub document_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Sighting {
+    pub first_seen_at: DateTime<Utc>,
+    pub submitter: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Entry {
+    fingerprint: Fingerprint,
+    #[serde(flatten)]
+    sighting: Sighting,
+}
+
+pub struct Registry {
+    path: PathBuf,
+}
+
+impl Registry {
+    pub fn new(path: PathBuf) -> Self {
+        Registry { path }
+    }
+
+    // the registry is optional, it is enabled by setting LA_TAUPE_2DDOC_REGISTRY
+    // to the path of the file used as a store
+    pub fn from_env() -> Option<Self> {
+        std::env::var("LA_TAUPE_2DDOC_REGISTRY")
+            .ok()
+            .filter(|path| !path.trim().is_empty())
+            .map(|path| Registry::new(PathBuf::from(path)))
+    }
+
+    // returns the first sighting if the fingerprint was already recorded,
+    // otherwise records it and returns None
+    pub fn record(
+        &self,
+        fingerprint: &Fingerprint,
+        submitter: Option<&str>,
+    ) -> Result<Option<Sighting>, String> {
+        let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(sighting) = self.find(fingerprint)? {
+            return Ok(Some(sighting));
+        }
+
+        let entry = Entry {
+            fingerprint: fingerprint.clone(),
+            sighting: Sighting {
+                first_seen_at: Utc::now(),
+                submitter: submitter.map(|s| s.to_string()),
+            },
+        };
+
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| format!("Failed to serialize registry entry: {}", e))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("Failed to open registry {}: {}", self.path.display(), e))?;
+
+        writeln!(file, "{}", line)
+            .map_err(|e| format!("Failed to write registry {}: {}", self.path.display(), e))?;
+
+        Ok(None)
+    }
+
+    fn find(&self, fingerprint: &Fingerprint) -> Result<Option<Sighting>, String> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let file = std::fs::File::open(&self.path)
+            .map_err(|e| format!("Failed to open registry {}: {}", self.path.display(), e))?;
+
+        let sighting = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str::<Entry>(&line).ok())
+            .find(|entry| &entry.fingerprint == fingerprint)
+            .map(|entry| entry.sighting);
+
+        Ok(sighting)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twoddoc::parse;
+
+    #[test]
+    fn test_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = Registry::new(dir.path().join("registry.jsonl"));
+
+        let i = "DC02FR000001125E125C0026FR245700010MLLE/SAMPLE/ANGELA2021BAT 2 ETG 32325METZ227 PLACE DES SPECIMENSZ2HSK7UZM6KPL7UL6OK7NR77GSPGPNNUYYEE4ZV75L5OCIWKVOXTV3I5AJLRSUDOIR76F75QY5Z7KLH3FACKHVF7JH3DYMRI5EIAZMI";
+        let fingerprint = parse(i).unwrap().fingerprint();
+
+        assert_eq!(fingerprint.autorite_certification, "FR00");
+        assert_eq!(fingerprint.identifiant_du_certificat, "0001");
+        assert_eq!(fingerprint.document_id, None);
+
+        assert_eq!(registry.record(&fingerprint, Some("dossier-1")), Ok(None));
+
+        let sighting = registry
+            .record(&fingerprint, Some("dossier-2"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(sighting.submitter, Some("dossier-1".to_string()));
+
+        let other = Fingerprint {
+            signature: "OTHER".to_string(),
+            ..fingerprint
+        };
+        assert_eq!(registry.record(&other, None), Ok(None));
+    }
+}
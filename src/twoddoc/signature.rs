@@ -1,6 +1,70 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
 use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use rsa::{
+    pkcs1::DecodeRsaPublicKey,
+    pkcs1v15,
+    sha2::{Sha256, Sha384, Sha512},
+    RsaPublicKey,
+};
+use serde::Serialize;
+use x509_cert::{der::Encode, time::Time, Certificate};
 
-use super::certificate_store::certificate;
+use super::{
+    certificate_store::{certificate, try_certificate},
+    trust_service::find_trust_service,
+};
+
+const SHA256_WITH_RSA: &str = "1.2.840.113549.1.1.11";
+const SHA384_WITH_RSA: &str = "1.2.840.113549.1.1.12";
+const SHA512_WITH_RSA: &str = "1.2.840.113549.1.1.13";
+
+#[derive(Debug, Serialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub serial_number: String,
+    pub not_before: Option<DateTime<Utc>>,
+    pub not_after: Option<DateTime<Utc>>,
+}
+
+impl From<&Certificate> for CertificateInfo {
+    fn from(certificate: &Certificate) -> Self {
+        let tbs = &certificate.tbs_certificate;
+
+        CertificateInfo {
+            subject: tbs.subject.to_string(),
+            issuer: tbs.issuer.to_string(),
+            serial_number: tbs.serial_number.to_string(),
+            not_before: to_date_time(tbs.validity.not_before),
+            not_after: to_date_time(tbs.validity.not_after),
+        }
+    }
+}
+
+impl CertificateInfo {
+    pub fn is_valid_at(&self, date: NaiveDateTime) -> bool {
+        let date = date.and_utc();
+
+        self.not_before.is_none_or(|not_before| not_before <= date)
+            && self.not_after.is_none_or(|not_after| date <= not_after)
+    }
+}
+
+fn to_date_time(time: Time) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(time.to_unix_duration().as_secs() as i64, 0)
+}
+
+#[derive(Debug, Serialize)]
+pub struct Verification {
+    pub autorite_certification: String,
+    pub identifiant_du_certificat: String,
+    pub signature_valid: bool,
+    pub certificate: Option<CertificateInfo>,
+    pub certificate_valid_at_signature: Option<bool>,
+    pub issuer: Option<CertificateInfo>,
+    pub chain_valid: Option<bool>,
+    pub errors: Vec<String>,
+}
 
 pub fn check(
     payload_str: &str,
@@ -15,6 +79,147 @@ pub fn check(
     verifying_key.verify(payload, &signature).unwrap();
 }
 
+// same as check, but reports what was verified instead of panicking
+pub fn verify(
+    payload_str: &str,
+    signature_str: &str,
+    autorite_certification: &str,
+    identifiant_du_certificat: &str,
+    date_creation_signature: NaiveDateTime,
+) -> Verification {
+    let mut verification = Verification {
+        autorite_certification: autorite_certification.to_string(),
+        identifiant_du_certificat: identifiant_du_certificat.to_string(),
+        signature_valid: false,
+        certificate: None,
+        certificate_valid_at_signature: None,
+        issuer: None,
+        chain_valid: None,
+        errors: vec![],
+    };
+
+    let certificate = match try_certificate(autorite_certification, identifiant_du_certificat) {
+        Ok(certificate) => certificate,
+        Err(e) => {
+            verification.errors.push(e);
+            return verification;
+        }
+    };
+
+    let info = CertificateInfo::from(&certificate);
+    verification.certificate_valid_at_signature = Some(info.is_valid_at(date_creation_signature));
+    verification.certificate = Some(info);
+
+    match check_payload(payload_str, signature_str, &certificate) {
+        Ok(()) => verification.signature_valid = true,
+        Err(e) => verification.errors.push(e),
+    }
+
+    match find_issuer(autorite_certification, &certificate) {
+        Ok(issuer) => {
+            match check_certificate_signature(&certificate, &issuer) {
+                Ok(()) => verification.chain_valid = Some(true),
+                Err(e) => {
+                    verification.chain_valid = Some(false);
+                    verification.errors.push(e);
+                }
+            }
+            verification.issuer = Some(CertificateInfo::from(&issuer));
+        }
+        Err(e) => verification.errors.push(e),
+    }
+
+    verification
+}
+
+fn check_payload(
+    payload_str: &str,
+    signature_str: &str,
+    certificate: &Certificate,
+) -> Result<(), String> {
+    let signature_bytes =
+        base32::decode(base32::Alphabet::Rfc4648 { padding: true }, signature_str)
+            .ok_or("Signature is not valid base32")?;
+    let signature =
+        Signature::from_slice(&signature_bytes).map_err(|e| format!("Invalid signature: {}", e))?;
+
+    let key = certificate
+        .tbs_certificate
+        .subject_public_key_info
+        .subject_public_key
+        .raw_bytes();
+    let verifying_key = VerifyingKey::from_sec1_bytes(key)
+        .map_err(|e| format!("Invalid certificate public key: {}", e))?;
+
+    verifying_key
+        .verify(payload_str.as_bytes(), &signature)
+        .map_err(|_| "Signature does not match the payload".to_string())
+}
+
+// the issuer of the certificate among the certificates of the trust service list
+fn find_issuer(
+    autorite_certification: &str,
+    certificate: &Certificate,
+) -> Result<Certificate, String> {
+    let service = find_trust_service(autorite_certification).ok_or(format!(
+        "{} is not in the trust service list",
+        autorite_certification
+    ))?;
+
+    let issuer = certificate.tbs_certificate.issuer.to_string();
+
+    service
+        .ca_certificates()
+        .into_iter()
+        .find(|ca| ca.tbs_certificate.subject.to_string() == issuer)
+        .ok_or(format!(
+            "Issuer {} not found in the trust service list",
+            issuer
+        ))
+}
+
+fn check_certificate_signature(
+    certificate: &Certificate,
+    issuer: &Certificate,
+) -> Result<(), String> {
+    let tbs = certificate
+        .tbs_certificate
+        .to_der()
+        .map_err(|e| format!("Failed to encode certificate: {}", e))?;
+
+    let signature = pkcs1v15::Signature::try_from(certificate.signature.raw_bytes())
+        .map_err(|e| format!("Invalid certificate signature: {}", e))?;
+
+    let public_key = RsaPublicKey::from_pkcs1_der(
+        issuer
+            .tbs_certificate
+            .subject_public_key_info
+            .subject_public_key
+            .raw_bytes(),
+    )
+    .map_err(|e| format!("Invalid issuer public key: {}", e))?;
+
+    let result = match certificate.signature_algorithm.oid.to_string().as_str() {
+        SHA256_WITH_RSA => {
+            pkcs1v15::VerifyingKey::<Sha256>::new(public_key).verify(&tbs, &signature)
+        }
+        SHA384_WITH_RSA => {
+            pkcs1v15::VerifyingKey::<Sha384>::new(public_key).verify(&tbs, &signature)
+        }
+        SHA512_WITH_RSA => {
+            pkcs1v15::VerifyingKey::<Sha512>::new(public_key).verify(&tbs, &signature)
+        }
+        oid => {
+            return Err(format!(
+                "Unsupported certificate signature algorithm: {}",
+                oid
+            ))
+        }
+    };
+
+    result.map_err(|_| "Certificate is not signed by its issuer".to_string())
+}
+
 fn base32_str_to_bytes(base32_str: &str) -> Vec<u8> {
     base32::decode(base32::Alphabet::Rfc4648 { padding: true }, base32_str).unwrap()
 }
@@ -1,5 +1,6 @@
 use serde::Deserialize;
 use url::Url;
+use x509_cert::{der::DecodePem, Certificate};
 
 static TSL_SIGNED_XML: &str = include_str!("tsl_signed.xml");
 
@@ -75,15 +76,45 @@ struct DigitalId {
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TrustService {
-    trade_name: String,
+    pub trade_name: String,
     pub information_url: Url,
     certificates: String,
+    service_certificates: Vec<String>,
 }
 
-pub fn trust_service(autorite_du_certificat: &str) -> TrustService {
+impl TrustService {
+    // the certificates of all the services of the provider,
+    // they are the issuers of the certificates signing the 2D-Docs
+    pub fn ca_certificates(&self) -> Vec<Certificate> {
+        self.service_certificates
+            .iter()
+            .filter_map(|base64| base64_to_certificate(base64))
+            .collect()
+    }
+}
+
+fn base64_to_certificate(base64: &str) -> Option<Certificate> {
+    let base64: String = base64.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let lines = base64
+        .as_bytes()
+        .chunks(64)
+        .map(|chunk| String::from_utf8_lossy(chunk).to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let pem = format!(
+        "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+        lines
+    );
+
+    Certificate::from_pem(pem.as_bytes()).ok()
+}
+
+pub fn trust_services() -> Vec<TrustService> {
     let parsed = serde_xml_rs::from_str::<TrustServiceStatusList>(TSL_SIGNED_XML).unwrap();
 
-    let trust_services = parsed
+    parsed
         .list
         .list
         .iter()
@@ -103,14 +134,31 @@ pub fn trust_service(autorite_du_certificat: &str) -> TrustService {
                 .digital_ids
                 .certificates
                 .clone(),
+            service_certificates: tsp
+                .services
+                .services
+                .iter()
+                .map(|service| {
+                    service
+                        .info
+                        .digital_identities
+                        .digital_ids
+                        .certificates
+                        .clone()
+                })
+                .collect(),
         })
-        .collect::<Vec<_>>();
+        .collect()
+}
 
-    trust_services
-        .iter()
+pub fn find_trust_service(autorite_du_certificat: &str) -> Option<TrustService> {
+    trust_services()
+        .into_iter()
         .find(|ts| ts.trade_name == autorite_du_certificat)
-        .unwrap()
-        .clone()
+}
+
+pub fn trust_service(autorite_du_certificat: &str) -> TrustService {
+    find_trust_service(autorite_du_certificat).unwrap()
 }
 
 pub fn trusted_repositories_urls() -> Vec<Url> {
@@ -150,4 +198,15 @@ mod tests {
             "MIIFnzCCA4egAwIBAgIIWGqMmtUG62gwDQYJKoZIhvcNAQENBQAwTjENMAsGA1UEAwwERlIwMTEcMBoGA1UECwwTMDAwMiA1MjA3NjkyMjUwMDAyNzESMBAGA1UECgwJQXJpYWRORVhUMQswCQYDVQQGEwJGUjAeFw0xMjA2MjYxNTIyMzRaFw0yMjA2MjYxNTIyMzRaME4xDTALBgNVBAMMBEZSMDExHDAaBgNVBAsMEzAwMDIgNTIwNzY5MjI1MDAwMjcxEjAQBgNVBAoMCUFyaWFkTkVYVDELMAkGA1UEBhMCRlIwggIiMA0GCSqGSIb3DQEBAQUAA4ICDwAwggIKAoICAQCqKLgVoE+hgLBeJ7t6kf2QFslItd6bkRolj3iZlvtVuYV//WOicYKuGWkjW/K2Zv2PySieo676qAsrEbchxwBMPpx12yCQoBckHxCB9mwiG+uyBiN3bukuPKlRxb7i+yNARt3hT+DDUuYqcwat2G4mP8MbHnCsbS2S1jikwWJeCPCa2vhNXSXVr2hNSFlQQEvfQzaaE4hDmwfrFqbyTMUAsTxSV3G6l513KWSY6ZAgMA9lB3KxMcpqvY7mHTClvAUMQYUKa4199NTJIvS6pNF0eLZ+JCAbMoQgMxAKS8VLRW6ovqgtdExD9fr664lAPAMpXFnb7mcLz9ovIhrOM33BZaJ+3zUwqMn5WYOxAPdbPqaf/ap2E3E07v5CeUgFpy4UdkHarUS6MYlbXY14dITwGpxBWyEB3LVpQE9GP1ZAKf4f+tBstS5m08G3xum6wMdpBVDJ3w8ao7KR+jUJcvzk4av4ZphI8Z5AyeTtLdAXFhffX8B12PmeLLPQmF2VK8zSC4MnkKAwU5D6leASDmgHIdN/mpRwWGEQSZqlKa3LQBXWIauNIHqlK777jgNe+EdifLcXIpoiQCVZCU3k+b9WAio0uWywdIZca8aHpKliGLgbOsyXUWIeskc6Gk/JopisdJwE3osGpU+CNU8Jipq4FMvnq4CL7y9FbEWXGDIEQwIDAQABo4GAMH4wHQYDVR0OBBYEFBGxw8fKDCm4PX28b3V6u3DuL9hJMBIGA1UdEwEB/wQIMAYBAf8CAQAwHwYDVR0jBBgwFoAUEbHDx8oMKbg9fbxvdXq7cO4v2EkwGAYDVR0gBBEwDzANBgsrBgEEAYKqUgoCAjAOBgNVHQ8BAf8EBAMCAUYwDQYJKoZIhvcNAQENBQADggIBAE+yUWliolk2HDG2/Iq2rOprnLqe50ixhvA2f5LB4vzcJSwZal9UxWe8TXgydSb693k+Uw+f+jpWj4j9UQmILNkfOzWNNQbm2GWCCTxsgzdAQRsvV7JesueL4JjnX59bqt45YPOcoKo184x5Bc0eb83drrQaJzRA18WKvpDLiHwUIs7rhX799CPG5BQoLRKQpE3hgGjSswPV6+xZ4HHh3+IUjohxZGZTN+r+mIqKIKBqJVHjy4C+x5X+yvmKpT0SUzaZ+rxcDEEyvLbvxFR2j3gtQx7ytmn3aCfY1pzdixmkqx473r6Kinz9NhoZ9RsnxpDOpnLaB2by0mVWPb6z56ki6GkziO2QbllXqW4UmTWymLAd8zt1nhGq/TUcQJBZIt1S1v9IgwGItfvKuMtFprwhba1YVErBKQU/Zb7WJrhzji3JZVWJZelEbp8iBTOZdAqicvVYTUsDql7G8lgQ4szNugeWryeEtSdG4ZGN7nfrrVsb0C0fNswutS7EeFy/ly4L7dDdA8Y/qKhjK1zbLKBimihbPEJqc4up8tF2tjMtJkqY2Oej4FyxS+TOXZjWJ9FWhnly4yxK0MYW82kw3HB5fsBPvEEnbSCZj70TCcvLRdoM48+r7wZvdZVGYxKQ0vr/T/sRICKboFzysRvhflOREXvnHZUL9UVeV8HqTOgo".to_string()
         );
     }
+
+    #[test]
+    fn test_ca_certificates() {
+        let certificates = trust_service("FR01").ca_certificates();
+
+        assert_eq!(certificates.len(), 1);
+        assert_eq!(
+            certificates[0].tbs_certificate.subject.to_string(),
+            "C=FR,O=AriadNEXT,OU=0002 52076922500027,CN=FR01"
+        );
+    }
 }